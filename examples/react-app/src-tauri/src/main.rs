@@ -16,10 +16,11 @@ fn main() {
                 tauri_plugin_spotlight::WindowConfig {
                     label: String::from("main"),
                     shortcut: String::from("Ctrl+Shift+J"),
-                    macos_window_level: None,
+                    ..Default::default()
                 },
             ]),
             global_close_shortcut: Some(String::from("Escape")),
+            ..Default::default()
         })))
         .invoke_handler(tauri::generate_handler![greet])
         .setup(|app| {
@@ -1,9 +1,211 @@
 import { invoke } from '@tauri-apps/api/tauri'
 
-export async function show () {
-  void invoke('plugin:spotlight|show')
+export async function show (label?: string): Promise<void> {
+  await invoke('plugin:spotlight|show', { label })
 }
 
-export async function hide () {
-  void invoke('plugin:spotlight|hide')
+export async function showWithoutFocus (label?: string): Promise<void> {
+  await invoke('plugin:spotlight|show_without_focus', { label })
+}
+
+export async function showOnMonitor (monitorIndex: number, label?: string): Promise<void> {
+  await invoke('plugin:spotlight|show_on_monitor', { label, monitorIndex })
+}
+
+export async function moveToNextMonitor (label?: string): Promise<void> {
+  await invoke('plugin:spotlight|move_to_next_monitor', { label })
+}
+
+export async function setNextPosition (x: number, y: number, label?: string): Promise<void> {
+  await invoke('plugin:spotlight|set_next_position', { label, x, y })
+}
+
+export async function setShowBlocked (blocked: boolean, label?: string): Promise<void> {
+  await invoke('plugin:spotlight|set_show_blocked', { label, blocked })
+}
+
+export async function hide (label?: string): Promise<void> {
+  await invoke('plugin:spotlight|hide', { label })
+}
+
+export async function hideAll (): Promise<void> {
+  await invoke('plugin:spotlight|hide_all')
+}
+
+export async function showAll (): Promise<void> {
+  await invoke('plugin:spotlight|show_all')
+}
+
+/** Only available with the `dev-tools` feature */
+export async function revealAllSpotlight (cascade = false): Promise<void> {
+  await invoke('plugin:spotlight|reveal_all_spotlight', { cascade })
+}
+
+export async function toggle (label?: string): Promise<void> {
+  await invoke('plugin:spotlight|toggle', { label })
+}
+
+export async function suspendAutoHide (label?: string): Promise<void> {
+  await invoke('plugin:spotlight|suspend_auto_hide', { label })
+}
+
+export async function resumeAutoHide (label?: string): Promise<void> {
+  await invoke('plugin:spotlight|resume_auto_hide', { label })
+}
+
+export async function resetIdleTimer (label?: string): Promise<void> {
+  await invoke('plugin:spotlight|reset_idle_timer', { label })
+}
+
+export async function setSpotlightAutoHide (autoHide: boolean, label?: string): Promise<void> {
+  await invoke('plugin:spotlight|set_spotlight_auto_hide', { label, autoHide })
+}
+
+export async function setSpotlightPinned (pinned: boolean, label?: string): Promise<void> {
+  await invoke('plugin:spotlight|set_spotlight_pinned', { label, pinned })
+}
+
+export async function setIgnoreCursorEvents (ignore: boolean, label?: string): Promise<void> {
+  await invoke('plugin:spotlight|set_ignore_cursor_events', { label, ignore })
+}
+
+/**
+ * Suspends auto-hide while an IME composition is in progress in the page, so that the candidate
+ * window of an input method doesn't hide the spotlight window mid-composition. Returns a function
+ * that stops watching compositions.
+ */
+export function suspendAutoHideWhileComposing (label?: string): () => void {
+  let composing = false
+  const onCompositionStart = (): void => {
+    composing = true
+    void suspendAutoHide(label)
+  }
+  const onCompositionEnd = (): void => {
+    composing = false
+    void resumeAutoHide(label)
+  }
+  document.addEventListener('compositionstart', onCompositionStart)
+  document.addEventListener('compositionend', onCompositionEnd)
+  return () => {
+    document.removeEventListener('compositionstart', onCompositionStart)
+    document.removeEventListener('compositionend', onCompositionEnd)
+    if (composing) {
+      onCompositionEnd()
+    }
+  }
+}
+
+export async function updateShortcut (label: string, shortcut: string): Promise<void> {
+  await invoke('plugin:spotlight|update_shortcut', { label, shortcut })
+}
+
+export async function setGlobalCloseShortcut (accelerator: string | null): Promise<void> {
+  await invoke('plugin:spotlight|set_global_close_shortcut', { accelerator })
+}
+
+export async function suspendAllShortcuts (): Promise<void> {
+  await invoke('plugin:spotlight|suspend_all_shortcuts')
+}
+
+export async function resumeAllShortcuts (): Promise<void> {
+  await invoke('plugin:spotlight|resume_all_shortcuts')
+}
+
+export async function setSpotlightSize (width: number, height: number, label?: string): Promise<void> {
+  await invoke('plugin:spotlight|set_spotlight_size', { label, width, height })
+}
+
+export async function animateHeight (targetHeight: number, durationMs: number, label?: string): Promise<void> {
+  await invoke('plugin:spotlight|animate_height', { label, targetHeight, durationMs })
+}
+
+export async function recenterSpotlight (label?: string): Promise<void> {
+  await invoke('plugin:spotlight|recenter_spotlight', { label })
+}
+
+export async function setSpotlightOpacity (opacity: number, label?: string): Promise<void> {
+  await invoke('plugin:spotlight|set_spotlight_opacity', { label, opacity })
+}
+
+export async function isShortcutAvailable (accelerator: string): Promise<boolean> {
+  return await invoke('plugin:spotlight|is_shortcut_available', { accelerator })
+}
+
+export interface SpotlightState {
+  visible: boolean
+  shortcut_registered: boolean
+  auto_hide: boolean
+}
+
+export async function getSpotlightState (label?: string): Promise<SpotlightState> {
+  return await invoke('plugin:spotlight|get_spotlight_state', { label })
+}
+
+export interface TargetMonitor {
+  name: string | null
+  position: { x: number, y: number }
+  size: { width: number, height: number }
+  scale_factor: number
+  work_area_position: { x: number, y: number }
+  work_area_size: { width: number, height: number }
+}
+
+export async function getTargetMonitor (label?: string): Promise<TargetMonitor | null> {
+  return await invoke('plugin:spotlight|get_target_monitor', { label })
+}
+
+export interface WindowConfig {
+  label: string
+  shortcut?: string
+  shortcuts?: string[]
+  shortcut_sequence?: string[]
+  sequence_timeout_ms?: number
+  macos_window_level?: number | 'normal' | 'floating' | 'modal_panel' | 'main_menu' | 'status' | 'pop_up_menu' | 'screen_saver'
+  position?: 'center_active_monitor' | 'center_own_monitor' | { at_cursor: { offset_x: number, offset_y: number } }
+  position_preset?: 'center' | 'bottom_center' | { top_center: { y_offset_ratio: number } }
+  auto_hide?: boolean
+  hide_on_escape?: boolean
+  close_shortcut?: string
+  hide_on_outside_click?: boolean
+  force_foreground?: boolean
+  remember_position?: boolean
+  macos_panel?: boolean
+  macos_activate_app?: boolean
+  join_all_spaces?: boolean
+  animation?: { duration_ms?: number, slide?: 'up' | 'down' | 'left' | 'right' }
+  hide_animation?: { duration_ms?: number, slide?: 'up' | 'down' | 'left' | 'right' }
+  background_effect?: 'none' | 'vibrancy' | 'acrylic' | 'mica'
+  toggle_debounce_ms?: number
+  reopen_cooldown_ms?: number
+  min_visible_ms?: number
+  always_on_top?: boolean
+  skip_taskbar?: boolean
+  reset_size_on_show?: { width: number, height: number }
+  focus_selector?: string
+  restore_previous_focus?: boolean
+  start_hidden?: boolean
+  constrain_to_monitor?: boolean
+  prevent_close?: boolean
+  auto_hide_after_ms?: number
+  idle_fade?: { after_ms: number, target_opacity: number }
+  grab_focus?: boolean
+  linked_windows?: string[]
+  ignore_cursor_events?: boolean
+  capture_pending_input?: boolean
+  toggle_behavior?: 'hide_when_visible' | 'flash_when_focused'
+  on_blur?: 'hide' | 'nothing' | { dim: number }
+  global?: boolean
+  dock_to?: { label: string, side: 'right' | 'left' | 'below' | 'above', gap?: number }
+}
+
+export async function listSpotlightWindows (): Promise<WindowConfig[]> {
+  return await invoke('plugin:spotlight|list_spotlight_windows')
+}
+
+export async function registerSpotlightWindow (windowConfig: WindowConfig): Promise<void> {
+  await invoke('plugin:spotlight|register_spotlight_window', { windowConfig })
+}
+
+export async function unregisterSpotlightWindow (label: string): Promise<void> {
+  await invoke('plugin:spotlight|unregister_spotlight_window', { label })
 }
@@ -0,0 +1,196 @@
+use std::{fmt, hash::{Hash, Hasher}, str::FromStr};
+use crate::Error;
+
+/// Keys with more than one name, by the name they are known by here
+const KEY_ALIASES: &[(&str, &str)] = &[
+    ("BACKQUOTE", "`"),
+    ("BRACKETLEFT", "["),
+    ("BRACKETRIGHT", "]"),
+    ("COMMA", ","),
+    ("PERIOD", "."),
+    ("QUOTE", "'"),
+    ("SEMICOLON", ";"),
+    ("SLASH", "/"),
+    ("ARROWDOWN", "DOWN"),
+    ("ARROWUP", "UP"),
+    ("ARROWLEFT", "LEFT"),
+    ("ARROWRIGHT", "RIGHT"),
+    ("ESC", "ESCAPE"),
+];
+
+/// The other keys the global shortcut manager knows, besides letters, digits and function keys
+const NAMED_KEYS: &[&str] = &[
+    "`", "[", "]", ",", ".", "'", ";", "/", "=", "-", "\\", "BACKSLASH", "PLUS", "BACKSPACE", "CAPSLOCK",
+    "CONTEXTMENU", "ENTER", "SPACE", "TAB", "CONVERT", "INSERT", "DELETE", "END", "HELP", "HOME", "PAGEDOWN",
+    "PAGEUP", "DOWN", "UP", "LEFT", "RIGHT", "NUMLOCK", "ESCAPE", "FN", "FNLOCK", "PRINTSCREEN", "SCROLLLOCK",
+    "PAUSE", "VOLUMEMUTE", "VOLUMEDOWN", "VOLUMEUP", "MEDIANEXTTRACK", "MEDIAPREVIOUSTRACK", "MEDIAPLAYPAUSE",
+    "LAUNCHMAIL", "SUSPEND",
+];
+
+/// Keys of the numpad, which are written with a `Num` or `Numpad` prefix
+const NUMPAD_KEYS: &[&str] = &[
+    "0", "1", "2", "3", "4", "5", "6", "7", "8", "9", "ADD", "BACKSPACE", "CLEAR", "COMMA", "DIVIDE", "SUBSTRACT",
+    "ENTER",
+];
+
+/// Bits of the modifiers held down for an accelerator
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+struct Modifiers(u8);
+
+impl Modifiers {
+    const CONTROL: Self = Self(1);
+    const ALT: Self = Self(1 << 1);
+    const SHIFT: Self = Self(1 << 2);
+    const SUPER: Self = Self(1 << 3);
+
+    fn insert(&mut self, other: Self) {
+        self.0 |= other.0;
+    }
+
+    fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+/// The key a name stands for, written the same way for every name of it, or `None` if the global
+/// shortcut manager doesn't know the name
+fn key_code(name: &str) -> Option<String> {
+    let name = name.to_uppercase();
+    if let Some(&(_, key)) = KEY_ALIASES.iter().find(|(alias, _)| *alias == name) {
+        return Some(key.to_string());
+    }
+    let is_letter_or_digit = name.len() == 1 && name.chars().all(|c| c.is_ascii_alphanumeric());
+    // Written without leading zeros, e.g. `F1` but not `F01`
+    let is_function_key = name
+        .strip_prefix('F')
+        .and_then(|number| number.parse::<u8>().ok().filter(|n| n.to_string() == number))
+        .map_or(false, |n| (1..=35).contains(&n));
+    if is_letter_or_digit || is_function_key || NAMED_KEYS.contains(&name.as_str()) {
+        return Some(name);
+    }
+    let numpad_key = name.strip_prefix("NUMPAD").or_else(|| name.strip_prefix("NUM"))?;
+    NUMPAD_KEYS.contains(&numpad_key).then(|| format!("NUMPAD{}", numpad_key))
+}
+
+/// A key combination such as `Ctrl+Shift+P`, parsed the same way the global shortcut manager parses
+/// it. Spellings of the same combination compare equal, e.g. `shift+ctrl+p` or `CmdOrCtrl+Shift+P`
+/// on Windows and Linux, and display the same way
+#[derive(Debug, Clone)]
+pub struct Accelerator {
+    modifiers: Modifiers,
+    key: String,
+    /// The main key as it was written
+    key_name: String,
+}
+
+impl FromStr for Accelerator {
+    type Err = Error;
+
+    fn from_str(accelerator: &str) -> Result<Self, Self::Err> {
+        let invalid = || Error::InvalidShortcut(accelerator.to_string());
+        let mut modifiers = Modifiers::default();
+        let mut tokens: Vec<&str> = accelerator.split('+').map(str::trim).collect();
+        // The main key comes last, after the modifiers
+        let key_name = tokens.pop().unwrap_or_default();
+        let key = key_code(key_name).ok_or_else(invalid)?;
+        for token in tokens {
+            let modifier = match token.to_uppercase().as_str() {
+                "OPTION" | "ALT" => Modifiers::ALT,
+                "CONTROL" | "CTRL" => Modifiers::CONTROL,
+                "COMMAND" | "CMD" | "SUPER" => Modifiers::SUPER,
+                "SHIFT" => Modifiers::SHIFT,
+                "COMMANDORCONTROL" | "COMMANDORCTRL" | "CMDORCTRL" | "CMDORCONTROL" if cfg!(target_os = "macos") => {
+                    Modifiers::SUPER
+                }
+                "COMMANDORCONTROL" | "COMMANDORCTRL" | "CMDORCTRL" | "CMDORCONTROL" => Modifiers::CONTROL,
+                _ => return Err(invalid()),
+            };
+            modifiers.insert(modifier);
+        }
+        Ok(Self { modifiers, key, key_name: key_name.to_string() })
+    }
+}
+
+impl PartialEq for Accelerator {
+    fn eq(&self, other: &Self) -> bool {
+        self.modifiers == other.modifiers && self.key == other.key
+    }
+}
+
+impl Eq for Accelerator {}
+
+impl Hash for Accelerator {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.modifiers.hash(state);
+        self.key.hash(state);
+    }
+}
+
+impl fmt::Display for Accelerator {
+    /// Modifiers come first, in the order `Ctrl`, `Alt`, `Shift`, `Super`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let modifiers = [
+            (Modifiers::CONTROL, "Ctrl"),
+            (Modifiers::ALT, "Alt"),
+            (Modifiers::SHIFT, "Shift"),
+            (Modifiers::SUPER, "Super"),
+        ];
+        for (modifier, name) in modifiers {
+            if self.modifiers.contains(modifier) {
+                write!(f, "{}+", name)?;
+            }
+        }
+        let mut chars = self.key_name.chars();
+        match chars.next() {
+            Some(first) => write!(f, "{}{}", first.to_uppercase(), chars.as_str()),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Fails if the accelerator can't be parsed the same way the global shortcut manager parses it
+pub(crate) fn validate_accelerator(accelerator: &str) -> Result<(), Error> {
+    Accelerator::from_str(accelerator).map(|_| ())
+}
+
+/// Whether both accelerators are the same key combination, those that don't parse only match
+/// themselves
+pub(crate) fn same_accelerator(a: &str, b: &str) -> bool {
+    a == b || matches!((Accelerator::from_str(a), Accelerator::from_str(b)), (Ok(a), Ok(b)) if a == b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{same_accelerator, validate_accelerator, Accelerator};
+
+    #[test]
+    fn accept_valid_accelerators() {
+        assert!(validate_accelerator("Ctrl+I").is_ok());
+        assert!(validate_accelerator("CmdOrCtrl+Shift+Space").is_ok());
+        assert!(validate_accelerator("Escape").is_ok());
+        assert!(validate_accelerator("Alt+F12").is_ok());
+        assert!(validate_accelerator("Shift+Num5").is_ok());
+    }
+
+    #[test]
+    fn reject_invalid_accelerators() {
+        assert!(validate_accelerator("").is_err());
+        assert!(validate_accelerator("Ctrl+").is_err());
+        assert!(validate_accelerator("Ctrl+I+J").is_err());
+        assert!(validate_accelerator("Ctrl+Foo").is_err());
+        assert!(validate_accelerator("Ctrl+F36").is_err());
+        assert!(validate_accelerator("Ctrl+Shift").is_err());
+    }
+
+    #[test]
+    fn normalize_modifier_order_and_case() {
+        let accelerator: Accelerator = "shift+control+p".parse().unwrap();
+        assert_eq!(accelerator, "Ctrl+Shift+P".parse().unwrap());
+        assert_eq!(accelerator.to_string(), "Ctrl+Shift+P");
+        assert!(same_accelerator("Shift+Ctrl+P", "Ctrl+Shift+P"));
+        assert!(!same_accelerator("Ctrl+P", "Ctrl+Shift+P"));
+        assert!(!same_accelerator("Ctrl+Foo", "Ctrl+Bar"));
+        assert!(same_accelerator("Ctrl+Esc", "Ctrl+Escape"));
+        assert!(same_accelerator("Alt+Num1", "Alt+Numpad1"));
+    }
+}
@@ -0,0 +1,765 @@
+use std::{
+    sync::{atomic::{AtomicU64, Ordering}, Mutex, MutexGuard},
+    time::Duration,
+};
+use tauri::{Manager, PhysicalPosition, PhysicalSize, Window, Wry};
+use crate::{AnimationConfig, Error, HideAnimation, SlideDirection};
+use crate::spotlight::SpotlightManager;
+
+const FRAME_INTERVAL: Duration = Duration::from_millis(16);
+/// Distance in logical pixels the window slides over
+const SLIDE_DISTANCE: f64 = 24.0;
+/// How long a window left alone takes to fade to its idle opacity
+const IDLE_FADE_DURATION: Duration = Duration::from_millis(1000);
+
+#[derive(Debug)]
+struct Animation {
+    label: String,
+    generation: u64,
+    /// 0 when the window is fully hidden, 1 when it is fully shown
+    progress: f64,
+    hiding: bool,
+    /// Whether the window is sliding off screen rather than fading out
+    slide_off: bool,
+    /// Position of the window once it is fully shown
+    rest_position: PhysicalPosition<i32>,
+}
+
+/// Generations of the running animations of each window, a new one supersedes them
+type Generations = Vec<(String, u64)>;
+
+/// Running animations of the spotlight windows and the opacities they animate between
+#[derive(Default, Debug)]
+pub(crate) struct Animations {
+    animations: Mutex<Vec<Animation>>,
+    /// Opacity of the windows once they are fully shown, for those that aren't fully opaque
+    rest_opacities: Mutex<Vec<(String, f64)>>,
+    next_generation: AtomicU64,
+    /// Generation of the idle fade of each window that is fading or faded after being left alone
+    idle_fades: Mutex<Generations>,
+    /// Windows dimmed after losing focus
+    dimmed: Mutex<Vec<String>>,
+    /// Generation of the running height animation of each window
+    resizes: Mutex<Generations>,
+}
+
+impl Animations {
+    /// Stops any running animation of a window that is gone, without touching the window
+    pub(crate) fn forget(&self, label: &str) -> Result<(), Error> {
+        lock(&self.animations, "animations")?.retain(|animation| animation.label != label);
+        lock(&self.rest_opacities, "rest opacities")?.retain(|(l, _)| l != label);
+        lock(&self.resizes, "resizes")?.retain(|(l, _)| l != label);
+        lock(&self.dimmed, "dimmed windows")?.retain(|l| l != label);
+        lock(&self.idle_fades, "idle fades")?.retain(|(l, _)| l != label);
+        Ok(())
+    }
+}
+
+fn lock<'a, T>(mutex: &'a Mutex<T>, name: &str) -> Result<MutexGuard<'a, T>, Error> {
+    mutex.lock().map_err(|_| Error::Mutex(format!("failed to lock {}", name)))
+}
+
+fn animations(window: &Window<Wry>) -> &Animations {
+    window.state::<SpotlightManager>().inner().animations()
+}
+
+fn next_generation(window: &Window<Wry>) -> u64 {
+    animations(window).next_generation.fetch_add(1, Ordering::SeqCst)
+}
+
+fn lock_animations(window: &Window<Wry>) -> Result<MutexGuard<'_, Vec<Animation>>, Error> {
+    lock(&animations(window).animations, "animations")
+}
+
+fn lock_rest_opacities(window: &Window<Wry>) -> Result<MutexGuard<'_, Vec<(String, f64)>>, Error> {
+    lock(&animations(window).rest_opacities, "rest opacities")
+}
+
+fn lock_resizes(window: &Window<Wry>) -> Result<MutexGuard<'_, Generations>, Error> {
+    lock(&animations(window).resizes, "resizes")
+}
+
+fn lock_dimmed(window: &Window<Wry>) -> Result<MutexGuard<'_, Vec<String>>, Error> {
+    lock(&animations(window).dimmed, "dimmed windows")
+}
+
+fn lock_idle_fades(window: &Window<Wry>) -> Result<MutexGuard<'_, Generations>, Error> {
+    lock(&animations(window).idle_fades, "idle fades")
+}
+
+fn rest_opacity(window: &Window<Wry>) -> f64 {
+    lock_rest_opacities(window)
+        .ok()
+        .and_then(|opacities| opacities.iter().find(|(l, _)| l == window.label()).map(|(_, opacity)| *opacity))
+        .unwrap_or(1.0)
+}
+
+/// Sets the opacity the window has once it is fully shown, clamped to 0..1. A running animation
+/// fades towards it instead
+pub(crate) fn set_rest_opacity(window: &Window<Wry>, opacity: f64) -> Result<(), Error> {
+    if cfg!(not(any(target_os = "macos", target_os = "windows", target_os = "linux"))) {
+        return Err(Error::UnsupportedOpacity);
+    }
+    let opacity = clamp_opacity(opacity);
+    {
+        let mut opacities = lock_rest_opacities(window)?;
+        opacities.retain(|(label, _)| label != window.label());
+        opacities.push((window.label().to_string(), opacity));
+    }
+    let animating = lock_animations(window)?.iter().any(|animation| animation.label == window.label());
+    if !animating {
+        set_opacity(window, opacity)?;
+    }
+    Ok(())
+}
+
+/// Dims the window to the given opacity, clamped to 0..1, without changing its rest opacity.
+/// Windows that are fading in or out are left alone
+pub(crate) fn dim(window: &Window<Wry>, opacity: f64) -> Result<(), Error> {
+    if is_animating(window) {
+        return Ok(());
+    }
+    set_opacity(window, clamp_opacity(opacity))?;
+    let mut dimmed = lock_dimmed(window)?;
+    if !dimmed.iter().any(|label| label == window.label()) {
+        dimmed.push(window.label().to_string());
+    }
+    Ok(())
+}
+
+/// Brings a dimmed window back to its rest opacity
+pub(crate) fn undim(window: &Window<Wry>) -> Result<(), Error> {
+    let was_dimmed = {
+        let mut dimmed = lock_dimmed(window)?;
+        let count = dimmed.len();
+        dimmed.retain(|label| label != window.label());
+        dimmed.len() != count
+    };
+    if was_dimmed && !is_animating(window) {
+        set_opacity(window, rest_opacity(window))?;
+    }
+    Ok(())
+}
+
+fn is_dimmed(window: &Window<Wry>) -> bool {
+    lock_dimmed(window).map(|dimmed| dimmed.iter().any(|l| l == window.label())).unwrap_or(false)
+}
+
+/// Slowly fades the window from its rest opacity to the given one, clamped to 0..1, after it was
+/// left alone. Windows that are fading in or out or are dimmed are left alone
+pub(crate) fn fade_idle(window: &Window<Wry>, opacity: f64) -> Result<(), Error> {
+    if is_animating(window) || is_dimmed(window) {
+        return Ok(());
+    }
+    let generation = next_generation(window);
+    {
+        let mut fades = lock_idle_fades(window)?;
+        fades.retain(|(label, _)| label != window.label());
+        fades.push((window.label().to_string(), generation));
+    }
+    let from = rest_opacity(window);
+    let to = clamp_opacity(opacity);
+    let frames = ((IDLE_FADE_DURATION.as_millis() / FRAME_INTERVAL.as_millis()) as u32).max(1);
+    let window = window.clone();
+    std::thread::spawn(move || {
+        for frame in 1..=frames {
+            std::thread::sleep(FRAME_INTERVAL);
+            if !is_idle_fading(&window, generation) {
+                return;
+            }
+            let opacity = from + (to - from) * frame as f64 / frames as f64;
+            let w = window.clone();
+            let result = window.run_on_main_thread(move || {
+                // Checked on the main thread so that an interaction in between isn't faded over
+                if !is_idle_fading(&w, generation) || is_animating(&w) || is_dimmed(&w) {
+                    return;
+                }
+                if let Err(err) = set_opacity(&w, opacity) {
+                    log_warn!("failed to fade idle spotlight window {}: {}", w.label(), err);
+                }
+            });
+            if let Err(err) = result {
+                log_warn!("failed to fade idle spotlight window {}: {}", window.label(), err);
+                return;
+            }
+        }
+    });
+    Ok(())
+}
+
+fn is_idle_fading(window: &Window<Wry>, generation: u64) -> bool {
+    lock_idle_fades(window)
+        .map(|fades| fades.iter().any(|(l, g)| l == window.label() && *g == generation))
+        .unwrap_or(false)
+}
+
+/// Brings a window that faded after being left alone back to its rest opacity right away
+pub(crate) fn end_idle_fade(window: &Window<Wry>) -> Result<(), Error> {
+    let was_fading = {
+        let mut fades = lock_idle_fades(window)?;
+        let count = fades.len();
+        fades.retain(|(label, _)| label != window.label());
+        fades.len() != count
+    };
+    if was_fading && !is_animating(window) && !is_dimmed(window) {
+        set_opacity(window, rest_opacity(window))?;
+    }
+    Ok(())
+}
+
+fn clamp_opacity(opacity: f64) -> f64 {
+    if opacity.is_nan() {
+        1.0
+    } else {
+        opacity.clamp(0.0, 1.0)
+    }
+}
+
+/// Returns the animation config of the window unless the user asked the OS to reduce motion
+pub(crate) fn animation_for(animation: &Option<AnimationConfig>) -> Option<AnimationConfig> {
+    animation.clone().filter(|_| !prefers_reduced_motion())
+}
+
+/// Returns the hide animation config of the window unless the user asked the OS to reduce motion
+pub(crate) fn hide_animation_for(hide_animation: &Option<HideAnimation>) -> Option<HideAnimation> {
+    hide_animation.clone().filter(|_| !prefers_reduced_motion())
+}
+
+/// Whether the window is fading out, in which case it is still visible but about to be hidden
+pub(crate) fn is_hiding(window: &Window<Wry>) -> bool {
+    lock_animations(window)
+        .map(|animations| animations.iter().any(|animation| animation.label == window.label() && animation.hiding))
+        .unwrap_or(false)
+}
+
+/// Where the window is once it is fully shown, if it is animating and thus somewhere else
+pub(crate) fn rest_position(window: &Window<Wry>) -> Option<PhysicalPosition<i32>> {
+    lock_animations(window)
+        .ok()
+        .and_then(|animations| animations.iter().find(|animation| animation.label == window.label()).map(|animation| animation.rest_position))
+}
+
+/// Whether the window is fading or sliding in or out
+pub(crate) fn is_animating(window: &Window<Wry>) -> bool {
+    lock_animations(window)
+        .map(|animations| animations.iter().any(|animation| animation.label == window.label()))
+        .unwrap_or(false)
+}
+
+/// Fades the window in if it is animated, otherwise makes sure no fade out is left running. Call it
+/// right before showing the window
+pub(crate) fn start_show(window: &Window<Wry>, animation: &Option<AnimationConfig>) -> Result<(), Error> {
+    match animation_for(animation) {
+        Some(animation) => animate_show(window, &animation),
+        None => cancel(window),
+    }
+}
+
+/// Starts fading the window in, call it right before showing the window. A running hide
+/// animation is reversed from where it currently is instead of starting over
+pub(crate) fn animate_show(window: &Window<Wry>, config: &AnimationConfig) -> Result<(), Error> {
+    let generation = next_generation(window);
+    // Queried before locking, the query may have to wait for the main thread
+    let current_position = window.outer_position()?;
+    let (from, rest_position) = {
+        let mut animations = lock_animations(window)?;
+        match animations.iter_mut().find(|animation| animation.label == window.label()) {
+            // A window sliding off screen is still fully opaque, it only has to be put back
+            Some(animation) if animation.slide_off => {
+                animation.generation = generation;
+                animation.hiding = false;
+                animation.slide_off = false;
+                animation.progress = 1.0;
+                (1.0, animation.rest_position)
+            }
+            Some(animation) => {
+                animation.generation = generation;
+                animation.hiding = false;
+                (animation.progress, animation.rest_position)
+            }
+            None => {
+                let rest_position = current_position;
+                animations.push(Animation {
+                    label: window.label().to_string(),
+                    generation,
+                    progress: 0.0,
+                    hiding: false,
+                    slide_off: false,
+                    rest_position,
+                });
+                (0.0, rest_position)
+            }
+        }
+    };
+    if from >= 1.0 {
+        finish(window, generation);
+        return reset(window, rest_position);
+    }
+    apply_frame(window, config, rest_position, from)?;
+    run(window, config, generation, from, 1.0, rest_position, |window, generation| {
+        finish(window, generation);
+    });
+    Ok(())
+}
+
+/// Starts fading the window out and calls `on_hidden` on the main thread once it's transparent,
+/// unless the window is shown again in the meantime. Does nothing if it is already fading out
+pub(crate) fn animate_hide<F>(window: &Window<Wry>, config: &AnimationConfig, on_hidden: F) -> Result<(), Error>
+where
+    F: FnOnce(&Window<Wry>) -> Result<(), Error> + Send + 'static,
+{
+    let generation = next_generation(window);
+    // Queried before locking, the query may have to wait for the main thread
+    let current_position = window.outer_position()?;
+    let (from, rest_position) = {
+        let mut animations = lock_animations(window)?;
+        match animations.iter_mut().find(|animation| animation.label == window.label()) {
+            Some(animation) if animation.hiding => return Ok(()),
+            Some(animation) => {
+                animation.generation = generation;
+                animation.hiding = true;
+                (animation.progress, animation.rest_position)
+            }
+            None => {
+                let rest_position = current_position;
+                animations.push(Animation {
+                    label: window.label().to_string(),
+                    generation,
+                    progress: 1.0,
+                    hiding: true,
+                    slide_off: false,
+                    rest_position,
+                });
+                (1.0, rest_position)
+            }
+        }
+    };
+    run(window, config, generation, from, 0.0, rest_position, move |window, generation| {
+        finish_hiding(window, generation, rest_position, on_hidden);
+    });
+    Ok(())
+}
+
+/// Slides the window off the edge of its monitor and calls `on_hidden` on the main thread once
+/// it's out of sight, unless the window is shown again in the meantime. The window is put back
+/// at its rest position after hiding it, so that it doesn't show up off screen next time. Does
+/// nothing if it is already hiding
+pub(crate) fn animate_slide_off<F>(window: &Window<Wry>, config: &HideAnimation, on_hidden: F) -> Result<(), Error>
+where
+    F: FnOnce(&Window<Wry>) -> Result<(), Error> + Send + 'static,
+{
+    let generation = next_generation(window);
+    // Queried before locking, the queries may have to wait for the main thread
+    let current_position = window.outer_position()?;
+    let size = window.outer_size()?;
+    let monitor = window.current_monitor().map_err(Error::MonitorQueryFailed)?;
+    let rest_position = {
+        let mut animations = lock_animations(window)?;
+        match animations.iter_mut().find(|animation| animation.label == window.label()) {
+            Some(animation) if animation.hiding => return Ok(()),
+            Some(animation) => {
+                animation.generation = generation;
+                animation.hiding = true;
+                animation.slide_off = true;
+                animation.progress = 1.0;
+                animation.rest_position
+            }
+            None => {
+                animations.push(Animation {
+                    label: window.label().to_string(),
+                    generation,
+                    progress: 1.0,
+                    hiding: true,
+                    slide_off: true,
+                    rest_position: current_position,
+                });
+                current_position
+            }
+        }
+    };
+    // A window that was still fading in slides off as it is
+    set_opacity(window, rest_opacity(window))?;
+    let (dx, dy) = match monitor {
+        Some(monitor) => off_screen_offset(config.direction(), *monitor.position(), *monitor.size(), rest_position, size),
+        None => {
+            let (x, y) = slide_offset(config.direction(), SLIDE_DISTANCE * window.scale_factor()?, 0.0);
+            (-x.round() as i32, -y.round() as i32)
+        }
+    };
+    let frames = ((config.duration().as_millis() / FRAME_INTERVAL.as_millis()) as u32).max(1);
+    let window = window.clone();
+    std::thread::spawn(move || {
+        for frame in 1..=frames {
+            std::thread::sleep(FRAME_INTERVAL);
+            let moved = ease_out(frame as f64 / frames as f64);
+            if !update_progress(&window, generation, 1.0 - moved) {
+                return;
+            }
+            let position = PhysicalPosition {
+                x: rest_position.x + (dx as f64 * moved).round() as i32,
+                y: rest_position.y + (dy as f64 * moved).round() as i32,
+            };
+            let w = window.clone();
+            let result = window.run_on_main_thread(move || {
+                if let Err(err) = w.set_position(position) {
+                    log_warn!("failed to animate spotlight window {}: {}", w.label(), err);
+                }
+            });
+            if let Err(err) = result {
+                log_warn!("failed to animate spotlight window {}: {}", window.label(), err);
+            }
+        }
+        finish_hiding(&window, generation, rest_position, on_hidden);
+    });
+    Ok(())
+}
+
+/// Hides the window once its hide animation is done and puts it back at its rest position
+fn finish_hiding<F>(window: &Window<Wry>, generation: u64, rest_position: PhysicalPosition<i32>, on_hidden: F)
+where
+    F: FnOnce(&Window<Wry>) -> Result<(), Error> + Send + 'static,
+{
+    let w = window.clone();
+    let result = window.run_on_main_thread(move || {
+        // Checked on the main thread so that a show in between can't be hidden right away
+        if !finish(&w, generation) {
+            return;
+        }
+        if let Err(err) = on_hidden(&w) {
+            log_error!("failed to hide spotlight window {}: {}", w.label(), err);
+        }
+        if let Err(err) = reset(&w, rest_position) {
+            log_warn!("failed to reset spotlight window {} after hiding it: {}", w.label(), err);
+        }
+    });
+    if let Err(err) = result {
+        log_error!("failed to hide spotlight window {}: {}", window.label(), err);
+    }
+}
+
+/// Stops any running animation of the window and brings it to its rest opacity and position
+pub(crate) fn cancel(window: &Window<Wry>) -> Result<(), Error> {
+    let animation = {
+        let mut animations = lock_animations(window)?;
+        match animations.iter().position(|animation| animation.label == window.label()) {
+            Some(i) => animations.remove(i),
+            None => return Ok(()),
+        }
+    };
+    reset(window, animation.rest_position)
+}
+
+/// Resizes the window to the given height over the duration, keeping its width and its top left
+/// corner where they are when the animation starts. A running height animation stops where it is
+/// and the new one starts from there
+pub(crate) fn animate_height(window: &Window<Wry>, height: u32, duration: Duration) -> Result<(), Error> {
+    let generation = next_generation(window);
+    {
+        let mut resizes = lock_resizes(window)?;
+        resizes.retain(|(label, _)| label != window.label());
+        resizes.push((window.label().to_string(), generation));
+    }
+    // Queried after superseding the running animation, so that it no longer moves the window
+    let from = window.inner_size()?;
+    let anchor = window.outer_position()?;
+    let frames = if prefers_reduced_motion() {
+        1
+    } else {
+        ((duration.as_millis() / FRAME_INTERVAL.as_millis()) as u32).max(1)
+    };
+    let window = window.clone();
+    std::thread::spawn(move || {
+        for frame in 1..=frames {
+            if frames > 1 {
+                std::thread::sleep(FRAME_INTERVAL);
+            }
+            if !is_current_resize(&window, generation) {
+                return;
+            }
+            let size = PhysicalSize {
+                width: from.width,
+                height: interpolate(from.height, height, ease_out(frame as f64 / frames as f64)),
+            };
+            let w = window.clone();
+            let result = window.run_on_main_thread(move || {
+                // Windows don't all grow downwards, so the top edge is put back in place
+                let result = w.set_size(size).and_then(|_| w.set_position(anchor));
+                if let Err(err) = result {
+                    log_warn!("failed to resize spotlight window {}: {}", w.label(), err);
+                }
+            });
+            if let Err(err) = result {
+                log_warn!("failed to resize spotlight window {}: {}", window.label(), err);
+                return;
+            }
+        }
+        if let Ok(mut resizes) = lock_resizes(&window) {
+            resizes.retain(|(label, g)| label != window.label() || *g != generation);
+        }
+    });
+    Ok(())
+}
+
+/// Stops any running height animation of the window, leaving it at its current size
+pub(crate) fn cancel_resize(window: &Window<Wry>) -> Result<(), Error> {
+    lock_resizes(window)?.retain(|(label, _)| label != window.label());
+    Ok(())
+}
+
+/// Whether the height of the window is being animated
+pub(crate) fn is_resizing(window: &Window<Wry>) -> bool {
+    lock_resizes(window)
+        .map(|resizes| resizes.iter().any(|(l, _)| l == window.label()))
+        .unwrap_or(false)
+}
+
+fn is_current_resize(window: &Window<Wry>, generation: u64) -> bool {
+    lock_resizes(window)
+        .map(|resizes| resizes.iter().any(|(l, g)| l == window.label() && *g == generation))
+        .unwrap_or(false)
+}
+
+fn interpolate(from: u32, to: u32, progress: f64) -> u32 {
+    (from as f64 + (to as f64 - from as f64) * progress).round() as u32
+}
+
+fn run<F>(window: &Window<Wry>, config: &AnimationConfig, generation: u64, from: f64, to: f64, rest_position: PhysicalPosition<i32>, on_done: F)
+where
+    F: FnOnce(&Window<Wry>, u64) + Send + 'static,
+{
+    let window = window.clone();
+    let config = config.clone();
+    // Partial animations, e.g. when reversing, take proportionally less time
+    let duration = config.duration().mul_f64((to - from).abs());
+    let frames = ((duration.as_millis() / FRAME_INTERVAL.as_millis()) as u32).max(1);
+    std::thread::spawn(move || {
+        for frame in 1..=frames {
+            std::thread::sleep(FRAME_INTERVAL);
+            let progress = from + (to - from) * ease_out(frame as f64 / frames as f64);
+            if !update_progress(&window, generation, progress) {
+                return;
+            }
+            if let Err(err) = apply_frame(&window, &config, rest_position, progress) {
+                log_warn!("failed to animate spotlight window {}: {}", window.label(), err);
+            }
+        }
+        on_done(&window, generation);
+    });
+}
+
+/// Records the progress of the animation, returns `false` if it has been superseded
+fn update_progress(window: &Window<Wry>, generation: u64, progress: f64) -> bool {
+    let mut animations = match lock_animations(window) {
+        Ok(animations) => animations,
+        Err(_) => return false,
+    };
+    match animations.iter_mut().find(|animation| animation.label == window.label() && animation.generation == generation) {
+        Some(animation) => {
+            animation.progress = progress;
+            true
+        }
+        None => false,
+    }
+}
+
+/// Removes the finished animation, returns `false` if it has been superseded
+fn finish(window: &Window<Wry>, generation: u64) -> bool {
+    let mut animations = match lock_animations(window) {
+        Ok(animations) => animations,
+        Err(_) => return false,
+    };
+    match animations.iter().position(|animation| animation.label == window.label() && animation.generation == generation) {
+        Some(i) => {
+            animations.remove(i);
+            true
+        }
+        None => false,
+    }
+}
+
+fn apply_frame(window: &Window<Wry>, config: &AnimationConfig, rest_position: PhysicalPosition<i32>, progress: f64) -> Result<(), Error> {
+    let position = config.slide.map(|direction| -> Result<_, Error> {
+        let (x, y) = slide_offset(direction, SLIDE_DISTANCE * window.scale_factor()?, progress);
+        Ok(PhysicalPosition {
+            x: rest_position.x + x.round() as i32,
+            y: rest_position.y + y.round() as i32,
+        })
+    }).transpose()?;
+    let w = window.clone();
+    let opacity = progress * rest_opacity(window);
+    window.run_on_main_thread(move || {
+        let result = set_opacity(&w, opacity).and_then(|_| match position {
+            Some(position) => w.set_position(position).map_err(Error::from),
+            None => Ok(()),
+        });
+        if let Err(err) = result {
+            log_warn!("failed to animate spotlight window {}: {}", w.label(), err);
+        }
+    })?;
+    Ok(())
+}
+
+fn reset(window: &Window<Wry>, rest_position: PhysicalPosition<i32>) -> Result<(), Error> {
+    set_opacity(window, rest_opacity(window))?;
+    window.set_position(rest_position)?;
+    Ok(())
+}
+
+fn ease_out(t: f64) -> f64 {
+    1.0 - (1.0 - t).powi(3)
+}
+
+/// Returns how far the window has to move in the direction to be entirely past the edge of the
+/// monitor
+fn off_screen_offset(
+    direction: SlideDirection,
+    monitor_position: PhysicalPosition<i32>,
+    monitor_size: PhysicalSize<u32>,
+    position: PhysicalPosition<i32>,
+    size: PhysicalSize<u32>,
+) -> (i32, i32) {
+    match direction {
+        SlideDirection::Up => (0, monitor_position.y - (position.y + size.height as i32)),
+        SlideDirection::Down => (0, monitor_position.y + monitor_size.height as i32 - position.y),
+        SlideDirection::Left => (monitor_position.x - (position.x + size.width as i32), 0),
+        SlideDirection::Right => (monitor_position.x + monitor_size.width as i32 - position.x, 0),
+    }
+}
+
+/// Returns how far the window is from its rest position at the given progress
+fn slide_offset(direction: SlideDirection, distance: f64, progress: f64) -> (f64, f64) {
+    let remaining = (1.0 - progress) * distance;
+    match direction {
+        SlideDirection::Up => (0.0, remaining),
+        SlideDirection::Down => (0.0, -remaining),
+        SlideDirection::Left => (remaining, 0.0),
+        SlideDirection::Right => (-remaining, 0.0),
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn set_opacity(window: &Window<Wry>, opacity: f64) -> Result<(), Error> {
+    use cocoa::{appkit::CGFloat, base::id};
+    use objc::{msg_send, sel, sel_impl};
+
+    let handle: id = window.ns_window().map_err(|_| Error::FailedToGetNSWindow)? as _;
+    let _: () = unsafe { msg_send![handle, setAlphaValue: opacity as CGFloat] };
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+fn set_opacity(window: &Window<Wry>, opacity: f64) -> Result<(), Error> {
+    use winapi::shared::windef::HWND;
+    use winapi::um::winuser::{
+        GetWindowLongPtrW, SetLayeredWindowAttributes, SetWindowLongPtrW, GWL_EXSTYLE, LWA_ALPHA, WS_EX_LAYERED,
+    };
+
+    let hwnd = window.hwnd().map_err(|_| Error::FailedToGetWindowHandle)?.0 as HWND;
+    unsafe {
+        // Only layered windows can be translucent
+        let style = GetWindowLongPtrW(hwnd, GWL_EXSTYLE);
+        if style & WS_EX_LAYERED as isize == 0 {
+            SetWindowLongPtrW(hwnd, GWL_EXSTYLE, style | WS_EX_LAYERED as isize);
+        }
+        SetLayeredWindowAttributes(hwnd, 0, (opacity * 255.0).round() as u8, LWA_ALPHA);
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+fn set_opacity(window: &Window<Wry>, opacity: f64) -> Result<(), Error> {
+    use gtk::prelude::WidgetExt;
+
+    window.gtk_window().map_err(|_| Error::FailedToGetWindowHandle)?.set_opacity(opacity);
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+fn set_opacity(_window: &Window<Wry>, _opacity: f64) -> Result<(), Error> {
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+fn prefers_reduced_motion() -> bool {
+    use cocoa::base::{id, BOOL, YES};
+    use objc::{class, msg_send, sel, sel_impl};
+
+    let workspace: id = unsafe { msg_send![class!(NSWorkspace), sharedWorkspace] };
+    let reduce_motion: BOOL = unsafe { msg_send![workspace, accessibilityDisplayShouldReduceMotion] };
+    reduce_motion == YES
+}
+
+#[cfg(target_os = "windows")]
+fn prefers_reduced_motion() -> bool {
+    use winapi::shared::minwindef::{BOOL, FALSE, TRUE};
+    use winapi::um::winuser::{SystemParametersInfoW, SPI_GETCLIENTAREAANIMATION};
+
+    let mut animations: BOOL = TRUE;
+    let ok = unsafe { SystemParametersInfoW(SPI_GETCLIENTAREAANIMATION, 0, &mut animations as *mut BOOL as _, 0) };
+    ok != 0 && animations == FALSE
+}
+
+#[cfg(target_os = "linux")]
+fn prefers_reduced_motion() -> bool {
+    use gtk::traits::SettingsExt;
+
+    // GTK settings may only be read on the main thread
+    gtk::is_initialized_main_thread()
+        && gtk::Settings::default().map_or(false, |settings| !settings.is_gtk_enable_animations())
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+fn prefers_reduced_motion() -> bool {
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{clamp_opacity, ease_out, interpolate, off_screen_offset, slide_offset};
+    use crate::SlideDirection;
+    use tauri::{PhysicalPosition, PhysicalSize};
+
+    #[test]
+    fn ease_out_starts_and_ends_in_place() {
+        assert_eq!(ease_out(0.0), 0.0);
+        assert_eq!(ease_out(1.0), 1.0);
+        assert!(ease_out(0.5) > 0.5);
+    }
+
+    #[test]
+    fn slide_towards_rest_position() {
+        assert_eq!(slide_offset(SlideDirection::Up, 24.0, 0.0), (0.0, 24.0));
+        assert_eq!(slide_offset(SlideDirection::Down, 24.0, 0.5), (0.0, -12.0));
+        assert_eq!(slide_offset(SlideDirection::Left, 24.0, 0.25), (18.0, 0.0));
+        assert_eq!(slide_offset(SlideDirection::Right, 24.0, 1.0), (0.0, 0.0));
+    }
+
+    #[test]
+    fn slide_past_monitor_edge() {
+        let monitor_position = PhysicalPosition { x: 1920, y: 0 };
+        let monitor_size = PhysicalSize { width: 1920, height: 1080 };
+        let position = PhysicalPosition { x: 2400, y: 200 };
+        let size = PhysicalSize { width: 800, height: 400 };
+        let offset = |direction| off_screen_offset(direction, monitor_position, monitor_size, position, size);
+        assert_eq!(offset(SlideDirection::Up), (0, -600));
+        assert_eq!(offset(SlideDirection::Down), (0, 880));
+        assert_eq!(offset(SlideDirection::Left), (-1280, 0));
+        assert_eq!(offset(SlideDirection::Right), (1440, 0));
+    }
+
+    #[test]
+    fn clamp_opacity_to_unit_range() {
+        assert_eq!(clamp_opacity(0.5), 0.5);
+        assert_eq!(clamp_opacity(-1.0), 0.0);
+        assert_eq!(clamp_opacity(2.0), 1.0);
+        assert_eq!(clamp_opacity(f64::NAN), 1.0);
+    }
+
+    #[test]
+    fn interpolate_height_both_ways() {
+        assert_eq!(interpolate(80, 400, 0.0), 80);
+        assert_eq!(interpolate(80, 400, 0.5), 240);
+        assert_eq!(interpolate(400, 80, 0.5), 240);
+        assert_eq!(interpolate(400, 80, 1.0), 80);
+    }
+}
@@ -0,0 +1,73 @@
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, Wry};
+use crate::{Error, ManagerExt};
+
+/// Keeps track of which window of the app has focus, as focus events come in
+#[derive(Default, Debug)]
+pub(crate) struct AppFocus {
+    /// Label of the window that got focus last, until it loses focus again
+    focused_window: Mutex<Option<String>>,
+    /// Labels of the windows that got focus, the one that got it last at the end
+    focus_order: Mutex<Vec<String>>,
+}
+
+impl AppFocus {
+    pub(crate) fn record(&self, label: &str, focused: bool) {
+        let mut focused_window = match self.focused_window.lock() {
+            Ok(focused_window) => focused_window,
+            Err(_) => return log_error!("failed to lock focused window"),
+        };
+        if focused {
+            *focused_window = Some(label.to_string());
+            if let Ok(mut order) = self.focus_order.lock() {
+                order.retain(|l| l != label);
+                order.push(label.to_string());
+            }
+        } else if focused_window.as_deref() == Some(label) {
+            *focused_window = None;
+        }
+    }
+
+    /// Label of the window of the app that has focus, as far as the focus events handled so far tell
+    pub(crate) fn focused_window(&self) -> Option<String> {
+        self.focused_window.lock().ok().and_then(|focused_window| focused_window.clone())
+    }
+
+    /// The one of the given windows that got focus last, `None` if none of them ever had focus
+    pub(crate) fn last_focused(&self, labels: &[String]) -> Option<String> {
+        let order = self.focus_order.lock().ok()?;
+        order.iter().rev().find(|label| labels.contains(label)).cloned()
+    }
+}
+
+/// Whether any window of the app has focus
+pub(crate) fn app_has_focus(app_handle: &AppHandle<Wry>) -> bool {
+    app_handle.windows().values().any(|window| window.is_focused().unwrap_or(false))
+}
+
+/// Registers the toggle shortcuts of spotlight windows that aren't `global` once a window of the
+/// app gets focus, and unregisters them once focus went to another app. Focus moving between
+/// windows of the app is only known once both events have been handled, so losing focus is checked
+/// again from the event loop
+pub(crate) fn handle_focus_change(app_handle: &AppHandle<Wry>, focused: bool) -> Result<(), Error> {
+    if focused {
+        return app_handle.spotlight().set_local_shortcuts_active(app_handle, true);
+    }
+    let handle = app_handle.clone();
+    // Running on the main thread right away would be too early, so the check is queued from another
+    std::thread::spawn(move || {
+        let app_handle = handle.clone();
+        let result = handle.run_on_main_thread(move || {
+            if app_has_focus(&app_handle) {
+                return;
+            }
+            if let Err(err) = app_handle.spotlight().set_local_shortcuts_active(&app_handle, false) {
+                log_error!("failed to unregister local spotlight shortcuts: {}", err);
+            }
+        });
+        if let Err(err) = result {
+            log_error!("failed to unregister local spotlight shortcuts: {}", err);
+        }
+    });
+    Ok(())
+}
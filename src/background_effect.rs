@@ -0,0 +1,126 @@
+use tauri::{Window, Wry};
+use crate::{BackgroundEffect, Error};
+
+/// Applies the background effect behind the webview of the window. Effects the platform doesn't
+/// have at all fail, effects the OS version is too old for are skipped
+pub(crate) fn apply_background_effect(window: &Window<Wry>, effect: BackgroundEffect) -> Result<(), Error> {
+    match effect {
+        BackgroundEffect::None => Ok(()),
+        effect => apply(window, effect),
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn apply(window: &Window<Wry>, effect: BackgroundEffect) -> Result<(), Error> {
+    use cocoa::{
+        appkit::{NSView, NSViewHeightSizable, NSViewWidthSizable},
+        base::{id, nil},
+        foundation::{NSInteger, NSRect},
+    };
+    use objc::{class, msg_send, sel, sel_impl};
+
+    #[allow(non_upper_case_globals)]
+    const NSVisualEffectMaterialPopover: NSInteger = 6;
+    #[allow(non_upper_case_globals)]
+    const NSVisualEffectBlendingModeBehindWindow: NSInteger = 0;
+    #[allow(non_upper_case_globals)]
+    const NSVisualEffectStateActive: NSInteger = 1;
+    #[allow(non_upper_case_globals)]
+    const NSWindowBelow: NSInteger = -1;
+
+    if effect != BackgroundEffect::Vibrancy {
+        return Err(Error::UnsupportedBackgroundEffect(format!("{:?}", effect)));
+    }
+    let handle: id = window.ns_window().map_err(|_| Error::FailedToGetNSWindow)? as _;
+    unsafe {
+        let content_view: id = msg_send![handle, contentView];
+        let bounds: NSRect = NSView::bounds(content_view);
+        let effect_view: id = msg_send![class!(NSVisualEffectView), alloc];
+        let effect_view: id = msg_send![effect_view, initWithFrame: bounds];
+        let _: () = msg_send![effect_view, setMaterial: NSVisualEffectMaterialPopover];
+        let _: () = msg_send![effect_view, setBlendingMode: NSVisualEffectBlendingModeBehindWindow];
+        let _: () = msg_send![effect_view, setState: NSVisualEffectStateActive];
+        let _: () = msg_send![effect_view, setAutoresizingMask: NSViewWidthSizable | NSViewHeightSizable];
+        let _: () = msg_send![content_view, addSubview: effect_view positioned: NSWindowBelow relativeTo: nil];
+    }
+    Ok(())
+}
+
+/// DWM window attribute and value enabling the effect on the given Windows build, if it has any
+#[cfg(any(target_os = "windows", test))]
+fn windows_backdrop(effect: BackgroundEffect, build: u32) -> Option<(u32, u32)> {
+    const DWMWA_SYSTEMBACKDROP_TYPE: u32 = 38;
+    const DWMWA_MICA_EFFECT: u32 = 1029;
+    const DWMSBT_MAINWINDOW: u32 = 2;
+    const DWMSBT_TRANSIENTWINDOW: u32 = 3;
+
+    match effect {
+        BackgroundEffect::Acrylic if build >= 22621 => Some((DWMWA_SYSTEMBACKDROP_TYPE, DWMSBT_TRANSIENTWINDOW)),
+        BackgroundEffect::Mica if build >= 22621 => Some((DWMWA_SYSTEMBACKDROP_TYPE, DWMSBT_MAINWINDOW)),
+        // The first release of Windows 11 only knows an undocumented attribute for Mica
+        BackgroundEffect::Mica if build >= 22000 => Some((DWMWA_MICA_EFFECT, 1)),
+        _ => None,
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn apply(window: &Window<Wry>, effect: BackgroundEffect) -> Result<(), Error> {
+    use winapi::shared::{minwindef::DWORD, ntdef::NTSTATUS, windef::HWND};
+    use winapi::um::{
+        dwmapi::{DwmExtendFrameIntoClientArea, DwmSetWindowAttribute},
+        uxtheme::MARGINS,
+        winnt::OSVERSIONINFOW,
+    };
+
+    #[link(name = "ntdll")]
+    extern "system" {
+        fn RtlGetVersion(version_information: *mut OSVERSIONINFOW) -> NTSTATUS;
+    }
+
+    if effect == BackgroundEffect::Vibrancy {
+        return Err(Error::UnsupportedBackgroundEffect(format!("{:?}", effect)));
+    }
+    let mut version: OSVERSIONINFOW = unsafe { std::mem::zeroed() };
+    version.dwOSVersionInfoSize = std::mem::size_of::<OSVERSIONINFOW>() as DWORD;
+    let build = if unsafe { RtlGetVersion(&mut version) } == 0 { version.dwBuildNumber } else { 0 };
+    let (attribute, value) = match windows_backdrop(effect, build) {
+        Some(backdrop) => backdrop,
+        None => {
+            log_info!("{:?} background is not available on Windows build {}, skipping it", effect, build);
+            return Ok(());
+        }
+    };
+    let hwnd = window.hwnd().map_err(|_| Error::FailedToGetWindowHandle)?.0 as HWND;
+    // The backdrop is drawn in the frame, so the frame has to cover the whole window
+    let margins = MARGINS { cxLeftWidth: -1, cxRightWidth: -1, cyTopHeight: -1, cyBottomHeight: -1 };
+    unsafe {
+        DwmExtendFrameIntoClientArea(hwnd, &margins);
+        DwmSetWindowAttribute(hwnd, attribute, &value as *const u32 as _, std::mem::size_of::<u32>() as DWORD);
+    }
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn apply(_window: &Window<Wry>, effect: BackgroundEffect) -> Result<(), Error> {
+    Err(Error::UnsupportedBackgroundEffect(format!("{:?}", effect)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::windows_backdrop;
+    use crate::BackgroundEffect;
+
+    #[test]
+    fn pick_backdrop_by_windows_build() {
+        assert_eq!(windows_backdrop(BackgroundEffect::Acrylic, 22621), Some((38, 3)));
+        assert_eq!(windows_backdrop(BackgroundEffect::Mica, 22631), Some((38, 2)));
+        assert_eq!(windows_backdrop(BackgroundEffect::Mica, 22000), Some((1029, 1)));
+    }
+
+    #[test]
+    fn skip_backdrop_on_older_windows() {
+        assert_eq!(windows_backdrop(BackgroundEffect::Acrylic, 22000), None);
+        assert_eq!(windows_backdrop(BackgroundEffect::Mica, 19045), None);
+        assert_eq!(windows_backdrop(BackgroundEffect::Vibrancy, 22621), None);
+    }
+}
@@ -0,0 +1,371 @@
+use tauri::PhysicalSize;
+use crate::config::{
+    AnimationConfig, BackgroundEffect, BlurAction, CloseScope, CycleGroup, DockTo, HideAnimation, IdleFade, MacosWindowLevel,
+    PluginConfig, PositionPreset, ToggleBehavior, WindowConfig, WindowPosition,
+};
+use crate::Error;
+
+/// Fluent alternative to writing a `WindowConfig` literal
+#[derive(Debug, Clone)]
+pub struct WindowConfigBuilder {
+    config: WindowConfig,
+}
+
+impl WindowConfigBuilder {
+    pub fn new(label: impl Into<String>) -> Self {
+        Self {
+            config: WindowConfig {
+                label: label.into(),
+                ..Default::default()
+            },
+        }
+    }
+
+    pub fn shortcut(mut self, shortcut: impl Into<String>) -> Self {
+        self.config.shortcut = shortcut.into();
+        self
+    }
+
+    /// Adds a shortcut on top of `shortcut`, can be called multiple times
+    pub fn additional_shortcut(mut self, shortcut: impl Into<String>) -> Self {
+        self.config.shortcuts.get_or_insert_with(Vec::new).push(shortcut.into());
+        self
+    }
+
+    /// Toggles the window when the accelerators are pressed one after the other
+    pub fn shortcut_sequence<S: Into<String>>(mut self, sequence: impl IntoIterator<Item = S>) -> Self {
+        self.config.shortcut_sequence = Some(sequence.into_iter().map(Into::into).collect());
+        self
+    }
+
+    pub fn sequence_timeout_ms(mut self, sequence_timeout_ms: u64) -> Self {
+        self.config.sequence_timeout_ms = Some(sequence_timeout_ms);
+        self
+    }
+
+    /// Takes a named level, e.g. `WindowTier::Floating`, or a raw NSWindow level
+    pub fn macos_window_level<L: Into<MacosWindowLevel>>(mut self, level: L) -> Self {
+        self.config.macos_window_level = Some(level.into());
+        self
+    }
+
+    pub fn position(mut self, position: WindowPosition) -> Self {
+        self.config.position = Some(position);
+        self
+    }
+
+    pub fn position_preset(mut self, position_preset: PositionPreset) -> Self {
+        self.config.position_preset = Some(position_preset);
+        self
+    }
+
+    pub fn auto_hide(mut self, auto_hide: bool) -> Self {
+        self.config.auto_hide = Some(auto_hide);
+        self
+    }
+
+    pub fn on_blur(mut self, on_blur: BlurAction) -> Self {
+        self.config.on_blur = Some(on_blur);
+        self
+    }
+
+    pub fn global(mut self, global: bool) -> Self {
+        self.config.global = Some(global);
+        self
+    }
+
+    pub fn hide_on_escape(mut self, hide_on_escape: bool) -> Self {
+        self.config.hide_on_escape = Some(hide_on_escape);
+        self
+    }
+
+    /// Hides just this window while it is focused, unlike the hide-everything `global_close_shortcut`
+    pub fn close_shortcut(mut self, shortcut: impl Into<String>) -> Self {
+        self.config.close_shortcut = Some(shortcut.into());
+        self
+    }
+
+    pub fn hide_on_outside_click(mut self, hide_on_outside_click: bool) -> Self {
+        self.config.hide_on_outside_click = Some(hide_on_outside_click);
+        self
+    }
+
+    pub fn force_foreground(mut self, force_foreground: bool) -> Self {
+        self.config.force_foreground = Some(force_foreground);
+        self
+    }
+
+    pub fn remember_position(mut self, remember_position: bool) -> Self {
+        self.config.remember_position = Some(remember_position);
+        self
+    }
+
+    pub fn macos_panel(mut self, macos_panel: bool) -> Self {
+        self.config.macos_panel = Some(macos_panel);
+        self
+    }
+
+    pub fn macos_activate_app(mut self, macos_activate_app: bool) -> Self {
+        self.config.macos_activate_app = Some(macos_activate_app);
+        self
+    }
+
+    pub fn join_all_spaces(mut self, join_all_spaces: bool) -> Self {
+        self.config.join_all_spaces = Some(join_all_spaces);
+        self
+    }
+
+    pub fn animation(mut self, animation: AnimationConfig) -> Self {
+        self.config.animation = Some(animation);
+        self
+    }
+
+    pub fn hide_animation(mut self, hide_animation: HideAnimation) -> Self {
+        self.config.hide_animation = Some(hide_animation);
+        self
+    }
+
+    pub fn dock_to(mut self, dock_to: DockTo) -> Self {
+        self.config.dock_to = Some(dock_to);
+        self
+    }
+
+    pub fn background_effect(mut self, background_effect: BackgroundEffect) -> Self {
+        self.config.background_effect = Some(background_effect);
+        self
+    }
+
+    pub fn toggle_behavior(mut self, toggle_behavior: ToggleBehavior) -> Self {
+        self.config.toggle_behavior = Some(toggle_behavior);
+        self
+    }
+
+    pub fn toggle_debounce_ms(mut self, toggle_debounce_ms: u64) -> Self {
+        self.config.toggle_debounce_ms = Some(toggle_debounce_ms);
+        self
+    }
+
+    pub fn reopen_cooldown_ms(mut self, reopen_cooldown_ms: u64) -> Self {
+        self.config.reopen_cooldown_ms = Some(reopen_cooldown_ms);
+        self
+    }
+
+    pub fn min_visible_ms(mut self, min_visible_ms: u64) -> Self {
+        self.config.min_visible_ms = Some(min_visible_ms);
+        self
+    }
+
+    pub fn always_on_top(mut self, always_on_top: bool) -> Self {
+        self.config.always_on_top = Some(always_on_top);
+        self
+    }
+
+    pub fn skip_taskbar(mut self, skip_taskbar: bool) -> Self {
+        self.config.skip_taskbar = Some(skip_taskbar);
+        self
+    }
+
+    pub fn reset_size_on_show(mut self, size: PhysicalSize<u32>) -> Self {
+        self.config.reset_size_on_show = Some(size);
+        self
+    }
+
+    pub fn focus_selector(mut self, selector: impl Into<String>) -> Self {
+        self.config.focus_selector = Some(selector.into());
+        self
+    }
+
+    pub fn restore_previous_focus(mut self, restore_previous_focus: bool) -> Self {
+        self.config.restore_previous_focus = Some(restore_previous_focus);
+        self
+    }
+
+    pub fn start_hidden(mut self, start_hidden: bool) -> Self {
+        self.config.start_hidden = Some(start_hidden);
+        self
+    }
+
+    pub fn constrain_to_monitor(mut self, constrain_to_monitor: bool) -> Self {
+        self.config.constrain_to_monitor = Some(constrain_to_monitor);
+        self
+    }
+
+    pub fn prevent_close(mut self, prevent_close: bool) -> Self {
+        self.config.prevent_close = Some(prevent_close);
+        self
+    }
+
+    pub fn auto_hide_after_ms(mut self, auto_hide_after_ms: u64) -> Self {
+        self.config.auto_hide_after_ms = Some(auto_hide_after_ms);
+        self
+    }
+
+    pub fn idle_fade(mut self, idle_fade: IdleFade) -> Self {
+        self.config.idle_fade = Some(idle_fade);
+        self
+    }
+
+    pub fn grab_focus(mut self, grab_focus: bool) -> Self {
+        self.config.grab_focus = Some(grab_focus);
+        self
+    }
+
+    pub fn ignore_cursor_events(mut self, ignore_cursor_events: bool) -> Self {
+        self.config.ignore_cursor_events = Some(ignore_cursor_events);
+        self
+    }
+
+    pub fn capture_pending_input(mut self, capture_pending_input: bool) -> Self {
+        self.config.capture_pending_input = Some(capture_pending_input);
+        self
+    }
+
+    /// Shows and hides the window with the given label along with this one, can be called multiple
+    /// times
+    pub fn linked_window(mut self, label: impl Into<String>) -> Self {
+        self.config.linked_windows.get_or_insert_with(Vec::new).push(label.into());
+        self
+    }
+
+    pub fn build(self) -> WindowConfig {
+        self.config
+    }
+}
+
+/// Fluent alternative to writing a `PluginConfig` literal, validated when calling `build`
+#[derive(Debug, Clone, Default)]
+pub struct PluginConfigBuilder {
+    windows: Vec<WindowConfig>,
+    global_close_shortcut: Option<String>,
+    global_close_scope: Option<CloseScope>,
+    global_toggle_shortcut: Option<String>,
+    cycle_groups: Vec<CycleGroup>,
+}
+
+impl PluginConfigBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn window(mut self, window: WindowConfig) -> Self {
+        self.windows.push(window);
+        self
+    }
+
+    pub fn global_close_shortcut(mut self, shortcut: impl Into<String>) -> Self {
+        self.global_close_shortcut = Some(shortcut.into());
+        self
+    }
+
+    pub fn global_close_scope(mut self, scope: CloseScope) -> Self {
+        self.global_close_scope = Some(scope);
+        self
+    }
+
+    pub fn global_toggle_shortcut(mut self, shortcut: impl Into<String>) -> Self {
+        self.global_toggle_shortcut = Some(shortcut.into());
+        self
+    }
+
+    pub fn cycle_group(mut self, group: CycleGroup) -> Self {
+        self.cycle_groups.push(group);
+        self
+    }
+
+    /// Fails with the first problem `PluginConfig::validate` finds
+    pub fn build(self) -> Result<PluginConfig, Error> {
+        let config = PluginConfig {
+            windows: if self.windows.is_empty() { None } else { Some(self.windows) },
+            global_close_shortcut: self.global_close_shortcut,
+            global_close_scope: self.global_close_scope,
+            global_toggle_shortcut: self.global_toggle_shortcut,
+            cycle_groups: if self.cycle_groups.is_empty() { None } else { Some(self.cycle_groups) },
+        };
+        if let Err(mut errors) = config.validate() {
+            return Err(errors.remove(0).into());
+        }
+        Ok(config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PluginConfigBuilder, WindowConfigBuilder};
+    use crate::config::{PluginConfig, WindowConfig};
+    use crate::Error;
+
+    #[test]
+    fn build_plugin_config() {
+        let config = PluginConfigBuilder::new()
+            .window(WindowConfigBuilder::new("main").shortcut("Cmd+Space").auto_hide(true).build())
+            .window(WindowConfigBuilder::new("foo").additional_shortcut("Ctrl+K").build())
+            .global_close_shortcut("Escape")
+            .build()
+            .unwrap();
+        assert_eq!(config, PluginConfig {
+            windows: Some(vec![
+                WindowConfig {
+                    label: String::from("main"),
+                    shortcut: String::from("Cmd+Space"),
+                    auto_hide: Some(true),
+                    ..Default::default()
+                },
+                WindowConfig {
+                    label: String::from("foo"),
+                    shortcuts: Some(vec![String::from("Ctrl+K")]),
+                    ..Default::default()
+                },
+            ]),
+            global_close_shortcut: Some(String::from("Escape")),
+            global_close_scope: None,
+            global_toggle_shortcut: None,
+            cycle_groups: None,
+        });
+    }
+
+    #[test]
+    fn reject_invalid_accelerator() {
+        let result = PluginConfigBuilder::new()
+            .window(WindowConfigBuilder::new("main").shortcut("Ctrl+Foo").build())
+            .build();
+        assert!(matches!(result, Err(Error::InvalidShortcut(shortcut)) if shortcut == "Ctrl+Foo"));
+        let result = PluginConfigBuilder::new().global_close_shortcut("Ctrl+").build();
+        assert!(matches!(result, Err(Error::InvalidShortcut(_))));
+    }
+
+    #[test]
+    fn reject_duplicate_label() {
+        let result = PluginConfigBuilder::new()
+            .window(WindowConfigBuilder::new("main").shortcut("Ctrl+I").build())
+            .window(WindowConfigBuilder::new("main").shortcut("Ctrl+J").build())
+            .build();
+        assert!(matches!(result, Err(Error::DuplicateWindowLabel(label)) if label == "main"));
+    }
+
+    #[test]
+    fn reject_shortcut_conflict() {
+        let result = PluginConfigBuilder::new()
+            .window(WindowConfigBuilder::new("main").shortcut("Ctrl+I").build())
+            .global_close_shortcut("Ctrl+I")
+            .build();
+        assert!(matches!(result, Err(Error::ShortcutConflict(_))));
+        let result = PluginConfigBuilder::new()
+            .global_close_shortcut("Ctrl+I")
+            .global_toggle_shortcut("Ctrl+I")
+            .build();
+        assert!(matches!(result, Err(Error::ShortcutConflict(_))));
+    }
+
+    #[test]
+    fn reject_sequence_step_conflict() {
+        let result = PluginConfigBuilder::new()
+            .window(WindowConfigBuilder::new("main").shortcut("Ctrl+I").build())
+            .window(WindowConfigBuilder::new("foo").shortcut_sequence(["Ctrl+Space", "Ctrl+I"]).build())
+            .build();
+        assert!(matches!(result, Err(Error::ShortcutConflict(_))));
+        let result = PluginConfigBuilder::new()
+            .window(WindowConfigBuilder::new("foo").shortcut_sequence(Vec::<String>::new()).build())
+            .build();
+        assert!(matches!(result, Err(Error::InvalidShortcut(_))));
+    }
+}
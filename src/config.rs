@@ -1,19 +1,745 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use tauri::PhysicalSize;
+use crate::accelerator::{same_accelerator, validate_accelerator};
+use crate::error::ConfigError;
+use crate::Error;
 
-#[derive(serde::Deserialize, Default, Debug, Clone, PartialEq)]
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum WindowPosition {
+    /// Re-center the window on the monitor that contains the cursor every time it is shown
+    CenterActiveMonitor,
+    /// Re-center the window on the monitor it is currently on every time it is shown, a window
+    /// that was never shown is centered on the primary monitor
+    CenterOwnMonitor,
+    /// Put the top-left corner of the window at the cursor every time it is shown, offset by
+    /// logical pixels, keeping the whole window in the work area of the monitor under the cursor
+    AtCursor { offset_x: i32, offset_y: i32 },
+}
+
+/// Where the window is placed in the work area of the monitor it is shown on, it is always
+/// centered horizontally
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum PositionPreset {
+    /// The top edge of the window sits at this ratio of the work area height from its top, e.g.
+    /// `0.2` for a fifth of the way down
+    TopCenter { y_offset_ratio: f64 },
+    Center,
+    BottomCenter,
+}
+
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SlideDirection {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum BackgroundEffect {
+    None,
+    /// `NSVisualEffectView` blur, macOS only
+    Vibrancy,
+    /// Windows 11 22H2 and later only
+    Acrylic,
+    /// Windows 11 only
+    Mica,
+}
+
+/// What a toggle shortcut does while the window is visible
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ToggleBehavior {
+    HideWhenVisible,
+    /// Emits `spotlight_flash` instead of hiding the window while it has focus, so that the page
+    /// can show that it is already there. It still hides while another window has focus
+    FlashWhenFocused,
+}
+
+/// What the window does when it loses focus
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum BlurAction {
+    Hide,
+    /// Stays visible at this opacity, clamped to 0..1, until it gets focus again
+    Dim(f64),
+    Nothing,
+}
+
+#[derive(serde::Deserialize, serde::Serialize, Default, Debug, Clone, PartialEq)]
+pub struct AnimationConfig {
+    pub duration_ms: Option<u64>,
+    /// Direction the window moves in while it is shown, it moves back the opposite way when hidden
+    pub slide: Option<SlideDirection>,
+}
+
+impl AnimationConfig {
+    /// Duration of a full show or hide animation, defaults to 150ms
+    pub fn duration(&self) -> std::time::Duration {
+        std::time::Duration::from_millis(self.duration_ms.unwrap_or(150))
+    }
+}
+
+/// Slides the window off the edge of its monitor when it is hidden
+#[derive(serde::Deserialize, serde::Serialize, Default, Debug, Clone, PartialEq)]
+pub struct HideAnimation {
+    pub duration_ms: Option<u64>,
+    /// Direction the window slides out in, defaults to `SlideDirection::Up`
+    pub slide: Option<SlideDirection>,
+}
+
+impl HideAnimation {
+    /// Duration of sliding the window off screen, defaults to 200ms
+    pub fn duration(&self) -> std::time::Duration {
+        std::time::Duration::from_millis(self.duration_ms.unwrap_or(200))
+    }
+
+    pub fn direction(&self) -> SlideDirection {
+        self.slide.unwrap_or(SlideDirection::Up)
+    }
+}
+
+/// Named NSWindow levels, from the lowest to the highest
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum WindowTier {
+    Normal,
+    Floating,
+    ModalPanel,
+    /// The level of panels, which sit above the menu bar
+    MainMenu,
+    Status,
+    PopUpMenu,
+    ScreenSaver,
+}
+
+/// NSWindow level of the window on macOS, either by name, e.g. `"floating"`, or as a number
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(untagged)]
+pub enum MacosWindowLevel {
+    Tier(WindowTier),
+    Level(i32),
+}
+
+impl MacosWindowLevel {
+    pub fn level(&self) -> i32 {
+        match self {
+            MacosWindowLevel::Tier(WindowTier::Normal) => 0,
+            MacosWindowLevel::Tier(WindowTier::Floating) => 3,
+            MacosWindowLevel::Tier(WindowTier::ModalPanel) => 8,
+            MacosWindowLevel::Tier(WindowTier::MainMenu) => 24,
+            MacosWindowLevel::Tier(WindowTier::Status) => 25,
+            MacosWindowLevel::Tier(WindowTier::PopUpMenu) => 101,
+            MacosWindowLevel::Tier(WindowTier::ScreenSaver) => 1000,
+            MacosWindowLevel::Level(level) => *level,
+        }
+    }
+}
+
+impl From<i32> for MacosWindowLevel {
+    fn from(level: i32) -> Self {
+        MacosWindowLevel::Level(level)
+    }
+}
+
+impl From<WindowTier> for MacosWindowLevel {
+    fn from(tier: WindowTier) -> Self {
+        MacosWindowLevel::Tier(tier)
+    }
+}
+
+/// Side of the anchor window a docked window sits on
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum DockSide {
+    Right,
+    Left,
+    Below,
+    Above,
+}
+
+/// Keeps the window next to another spotlight window, e.g. a preview pane beside the search window
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, PartialEq)]
+pub struct DockTo {
+    /// Label of the anchor window
+    pub label: String,
+    pub side: DockSide,
+    /// Space between the windows in logical pixels
+    pub gap: Option<f64>,
+}
+
+impl DockTo {
+    /// Space between the windows in logical pixels, defaults to 0
+    pub fn gap(&self) -> f64 {
+        self.gap.unwrap_or(0.0)
+    }
+}
+
+/// Slowly fades the window to a low opacity once it is left alone, e.g. a long-lived HUD. It is
+/// back at its full opacity as soon as the user interacts with it
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, PartialEq)]
+pub struct IdleFade {
+    pub after_ms: u64,
+    pub target_opacity: f64,
+}
+
+impl IdleFade {
+    pub fn after(&self) -> std::time::Duration {
+        std::time::Duration::from_millis(self.after_ms)
+    }
+
+    /// Opacity the window fades to, clamped to 0..1
+    pub fn target(&self) -> f64 {
+        if self.target_opacity.is_nan() {
+            1.0
+        } else {
+            self.target_opacity.clamp(0.0, 1.0)
+        }
+    }
+}
+
+#[derive(serde::Deserialize, serde::Serialize, Default, Debug, Clone, PartialEq)]
 pub struct WindowConfig {
     pub label: String,
+    #[serde(default)]
     pub shortcut: String,
-    pub macos_window_level: Option<i32>,
+    pub shortcuts: Option<Vec<String>>,
+    /// Accelerators toggling the window when pressed one after the other, e.g. a leader key
+    /// followed by a letter
+    pub shortcut_sequence: Option<Vec<String>>,
+    pub sequence_timeout_ms: Option<u64>,
+    /// Re-asserted every time the window is shown, in case macOS reset it while it was hidden
+    pub macos_window_level: Option<MacosWindowLevel>,
+    pub position: Option<WindowPosition>,
+    pub position_preset: Option<PositionPreset>,
+    pub auto_hide: Option<bool>,
+    pub hide_on_escape: Option<bool>,
+    pub close_shortcut: Option<String>,
+    pub hide_on_outside_click: Option<bool>,
+    pub force_foreground: Option<bool>,
+    pub remember_position: Option<bool>,
+    pub macos_panel: Option<bool>,
+    pub macos_activate_app: Option<bool>,
+    pub join_all_spaces: Option<bool>,
+    pub animation: Option<AnimationConfig>,
+    /// Takes the place of `animation` when the window is hidden
+    pub hide_animation: Option<HideAnimation>,
+    pub background_effect: Option<BackgroundEffect>,
+    pub toggle_debounce_ms: Option<u64>,
+    pub reopen_cooldown_ms: Option<u64>,
+    pub min_visible_ms: Option<u64>,
+    pub always_on_top: Option<bool>,
+    pub skip_taskbar: Option<bool>,
+    /// Size the window is brought back to every time it is shown after being hidden
+    pub reset_size_on_show: Option<PhysicalSize<u32>>,
+    /// CSS selector of the element focused every time the window is shown, e.g. its search field
+    pub focus_selector: Option<String>,
+    pub restore_previous_focus: Option<bool>,
+    /// Hides the window as soon as the plugin sees it, before it is set up
+    pub start_hidden: Option<bool>,
+    /// Moves the window back onto the monitors whenever it is dragged off them
+    pub constrain_to_monitor: Option<bool>,
+    /// Hides the window instead of closing it when closing is requested, e.g. from its close button
+    pub prevent_close: Option<bool>,
+    /// Hides the window once it is left alone for this long, even while it has focus
+    pub auto_hide_after_ms: Option<u64>,
+    pub idle_fade: Option<IdleFade>,
+    /// Shows the window without taking focus from the app the user is in, it doesn't hide on blur
+    /// then
+    pub grab_focus: Option<bool>,
+    /// Labels of the windows shown and hidden along with this one
+    pub linked_windows: Option<Vec<String>>,
+    /// Lets mouse clicks pass through the window to whatever is beneath it
+    pub ignore_cursor_events: Option<bool>,
+    /// Reports the characters typed right after showing the window, before any input has focus
+    pub capture_pending_input: Option<bool>,
+    pub toggle_behavior: Option<ToggleBehavior>,
+    /// Takes precedence over `auto_hide`
+    pub on_blur: Option<BlurAction>,
+    /// Registers the toggle shortcuts only while a window of the app has focus when `false`
+    pub global: Option<bool>,
+    /// Follows the anchor window around while both are visible, and hides along with it
+    pub dock_to: Option<DockTo>,
+}
+
+impl WindowConfig {
+    /// Returns `shortcut` followed by the additional `shortcuts`, skipping empty ones
+    pub fn all_shortcuts(&self) -> Vec<String> {
+        std::iter::once(&self.shortcut)
+            .chain(self.shortcuts.iter().flatten())
+            .filter(|shortcut| !shortcut.is_empty())
+            .cloned()
+            .collect()
+    }
+
+    /// Fails if any of the window's shortcuts isn't a valid accelerator
+    pub(crate) fn validate_shortcuts(&self) -> Result<(), Error> {
+        for shortcut in self.all_shortcuts().iter().chain(&self.close_shortcut).chain(self.shortcut_sequence.iter().flatten()) {
+            validate_accelerator(shortcut)?;
+        }
+        Ok(())
+    }
+
+    /// Each step of `shortcut_sequence` has to follow the previous one within this duration, defaults
+    /// to 1000ms
+    pub fn sequence_timeout(&self) -> std::time::Duration {
+        std::time::Duration::from_millis(self.sequence_timeout_ms.unwrap_or(1000))
+    }
+
+    /// Shortcut hiding just this window while it is focused, `hide_on_escape` is a shorthand for
+    /// Escape
+    pub fn window_close_shortcut(&self) -> Option<String> {
+        match &self.close_shortcut {
+            Some(close_shortcut) => Some(close_shortcut.clone()),
+            None if self.hide_on_escape.unwrap_or(false) => Some(String::from("Escape")),
+            None => None,
+        }
+    }
+
+    /// What the toggle shortcuts do while the window is visible, defaults to
+    /// `ToggleBehavior::HideWhenVisible`
+    pub fn toggle_behavior(&self) -> ToggleBehavior {
+        self.toggle_behavior.unwrap_or(ToggleBehavior::HideWhenVisible)
+    }
+
+    /// Where the window is placed on the active monitor, defaults to `PositionPreset::Center`
+    pub fn placement(&self) -> PositionPreset {
+        self.position_preset.unwrap_or(PositionPreset::Center)
+    }
+
+    /// Whether the window hides itself when it loses focus, defaults to `true` unless
+    /// `hide_on_outside_click` is enabled
+    pub fn should_auto_hide(&self) -> bool {
+        self.blur_action() == BlurAction::Hide
+    }
+
+    /// What the window does when it loses focus, `auto_hide` picks between `BlurAction::Hide` and
+    /// `BlurAction::Nothing` unless `on_blur` is set
+    pub fn blur_action(&self) -> BlurAction {
+        self.on_blur.unwrap_or_else(|| {
+            if self.auto_hide.unwrap_or(!self.should_hide_on_outside_click()) {
+                BlurAction::Hide
+            } else {
+                BlurAction::Nothing
+            }
+        })
+    }
+
+    /// Whether showing the window forcibly brings it in front of other apps, defaults to `true`
+    pub fn should_force_foreground(&self) -> bool {
+        self.force_foreground.unwrap_or(true)
+    }
+
+    /// Whether the window hides itself when a mouse button is pressed outside of it
+    pub fn should_hide_on_outside_click(&self) -> bool {
+        self.hide_on_outside_click.unwrap_or(false)
+    }
+
+    /// Whether the window is converted to a non-activating panel that shows over fullscreen apps on
+    /// macOS, defaults to `true`
+    pub fn should_use_macos_panel(&self) -> bool {
+        self.macos_panel.unwrap_or(true)
+    }
+
+    /// Whether showing the window activates the whole app on macOS, defaults to `false` for panels.
+    /// Regular windows don't get keyboard input while the app is inactive, so they default to `true`
+    pub fn should_activate_macos_app(&self) -> bool {
+        self.macos_activate_app.unwrap_or(!self.should_use_macos_panel())
+    }
+
+    /// Whether the window shows on the current space instead of the one it was created on, on
+    /// macOS, defaults to `true` for panels and `false` for regular windows
+    pub fn should_join_all_spaces(&self) -> bool {
+        self.join_all_spaces.unwrap_or(self.should_use_macos_panel())
+    }
+
+    /// Toggles of the window within this duration of the previous one are ignored, defaults to 150ms
+    pub fn toggle_debounce(&self) -> std::time::Duration {
+        std::time::Duration::from_millis(self.toggle_debounce_ms.unwrap_or(150))
+    }
+
+    /// The window isn't hidden by focus loss within this duration of being shown, defaults to 0ms
+    pub fn reopen_cooldown(&self) -> std::time::Duration {
+        std::time::Duration::from_millis(self.reopen_cooldown_ms.unwrap_or(0))
+    }
+
+    /// The window isn't hidden by focus loss or outside clicks until it has been visible this long,
+    /// defaults to 0ms
+    pub fn min_visible(&self) -> std::time::Duration {
+        std::time::Duration::from_millis(self.min_visible_ms.unwrap_or(0))
+    }
+
+    /// Whether the window stays above other windows, re-asserted every time it is shown, defaults to
+    /// `false`
+    pub fn should_stay_on_top(&self) -> bool {
+        self.always_on_top.unwrap_or(false)
+    }
+
+    /// Whether the window is left out of the taskbar, the dock and window switchers, defaults to
+    /// `false`
+    pub fn should_skip_taskbar(&self) -> bool {
+        self.skip_taskbar.unwrap_or(false)
+    }
+
+    /// Whether hiding the window gives focus back to the window or app that had it before the window
+    /// was shown, defaults to `false`
+    pub fn should_restore_previous_focus(&self) -> bool {
+        self.restore_previous_focus.unwrap_or(false)
+    }
+
+    /// Whether the window is hidden as soon as it is created, defaults to `false`
+    pub fn should_start_hidden(&self) -> bool {
+        self.start_hidden.unwrap_or(false)
+    }
+
+    /// Whether the toggle shortcuts work while other apps have focus, defaults to `true`
+    pub fn is_global(&self) -> bool {
+        self.global.unwrap_or(true)
+    }
+
+    /// Whether showing the window gives it focus, defaults to `true`
+    pub fn should_grab_focus(&self) -> bool {
+        self.grab_focus.unwrap_or(true)
+    }
+
+    /// Whether characters typed before an input has focus are reported with the
+    /// `spotlight_pending_input` event, defaults to `false`
+    pub fn should_capture_pending_input(&self) -> bool {
+        self.capture_pending_input.unwrap_or(false)
+    }
+
+    /// Whether clicks pass through the window, defaults to `false`
+    pub fn should_ignore_cursor_events(&self) -> bool {
+        self.ignore_cursor_events.unwrap_or(false)
+    }
+
+    /// How long the window stays shown without interaction, it never hides on its own by default
+    pub fn auto_hide_after(&self) -> Option<std::time::Duration> {
+        self.auto_hide_after_ms.map(std::time::Duration::from_millis)
+    }
+
+    /// Whether the window is kept from being dragged off the monitors, defaults to `false`
+    pub fn should_constrain_to_monitor(&self) -> bool {
+        self.constrain_to_monitor.unwrap_or(false)
+    }
+
+    /// Whether the window hides instead of closing, defaults to `false`
+    pub fn should_prevent_close(&self) -> bool {
+        self.prevent_close.unwrap_or(false)
+    }
+
+    /// Whether the window is kept where the user left it across hides and app restarts instead of
+    /// being repositioned on show, defaults to `false`
+    pub fn should_remember_position(&self) -> bool {
+        self.remember_position.unwrap_or(false)
+    }
+}
+
+/// What the shortcut of a cycle group does while the last window of the group is showing
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum CycleWrap {
+    /// Hides the last window and shows the first one again
+    ShowFirst,
+    /// Hides every window of the group, the next press starts over with the first one
+    HideAll,
+}
+
+/// A shortcut showing the windows one after the other, hiding the previous one each time
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, PartialEq)]
+pub struct CycleGroup {
+    pub shortcut: String,
+    pub labels: Vec<String>,
+    pub wrap: Option<CycleWrap>,
+}
+
+impl CycleGroup {
+    /// What happens past the last window, defaults to `CycleWrap::ShowFirst`
+    pub fn wrap(&self) -> CycleWrap {
+        self.wrap.unwrap_or(CycleWrap::ShowFirst)
+    }
+}
+
+/// Which spotlight windows the global close shortcut hides
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum CloseScope {
+    All,
+    /// The focused spotlight window, or the visible one that had focus last, along with its
+    /// linked windows
+    Frontmost,
 }
 
 #[derive(serde::Deserialize, Default, Debug, Clone, PartialEq)]
 pub struct PluginConfig {
     pub windows: Option<Vec<WindowConfig>>,
     pub global_close_shortcut: Option<String>,
+    pub global_close_scope: Option<CloseScope>,
+    /// Hides every spotlight window if any is visible, otherwise shows the first one in `windows`
+    pub global_toggle_shortcut: Option<String>,
+    pub cycle_groups: Option<Vec<CycleGroup>>,
 }
 
 impl PluginConfig {
+    /// Which windows the global close shortcut hides, defaults to all of them
+    pub fn close_scope(&self) -> CloseScope {
+        self.global_close_scope.unwrap_or(CloseScope::All)
+    }
+
+    /// Returns a description of whatever else already uses the shortcut besides the given window
+    pub fn shortcut_owner(&self, shortcut: &str, label: &str) -> Option<String> {
+        let mut windows = self.windows.iter().flatten();
+        if let Some(window) = windows.find(|window| {
+            window.label != label && window.all_shortcuts().iter().any(|s| same_accelerator(s, shortcut))
+        }) {
+            return Some(format!("window {}", window.label));
+        }
+        if let Some(window) = self.windows.iter().flatten().find(|window| {
+            window.label != label && window.window_close_shortcut().map_or(false, |s| same_accelerator(&s, shortcut))
+        }) {
+            return Some(format!("the close shortcut of window {}", window.label));
+        }
+        if let Some(window) = self.windows.iter().flatten().find(|window| {
+            window.label != label && window.shortcut_sequence.iter().flatten().any(|s| same_accelerator(s, shortcut))
+        }) {
+            return Some(format!("the shortcut sequence of window {}", window.label));
+        }
+        if self.global_close_shortcut.as_deref().map_or(false, |s| same_accelerator(s, shortcut)) {
+            return Some(String::from("the global close shortcut"));
+        }
+        if self.global_toggle_shortcut.as_deref().map_or(false, |s| same_accelerator(s, shortcut)) {
+            return Some(String::from("the global toggle shortcut"));
+        }
+        if let Some(group) = self.cycle_group(shortcut) {
+            return Some(format!("the cycle group of {}", group.labels.join(", ")));
+        }
+        None
+    }
+
+    /// The cycle group the shortcut advances
+    pub(crate) fn cycle_group(&self, shortcut: &str) -> Option<&CycleGroup> {
+        self.cycle_groups.iter().flatten().find(|group| same_accelerator(&group.shortcut, shortcut))
+    }
+
+    /// Fails if any toggle shortcut of the window is used by another window or the close shortcut
+    pub fn check_shortcut_conflict(&self, window_config: &WindowConfig) -> Result<(), Error> {
+        for shortcut in window_config.all_shortcuts() {
+            if let Some(owner) = self.shortcut_owner(&shortcut, &window_config.label) {
+                return Err(Error::ShortcutConflict(format!(
+                    "{} of window {} is already used by {}",
+                    shortcut, window_config.label, owner,
+                )));
+            }
+        }
+        for step in window_config.shortcut_sequence.iter().flatten() {
+            let owner = self
+                .shortcut_owner(step, &window_config.label)
+                .or_else(|| {
+                    let own = window_config.all_shortcuts().iter().any(|s| same_accelerator(s, step));
+                    own.then(|| format!("window {}", window_config.label))
+                });
+            if let Some(owner) = owner {
+                return Err(Error::ShortcutConflict(format!(
+                    "step {} of the shortcut sequence of window {} is already used by {}",
+                    step, window_config.label, owner,
+                )));
+            }
+        }
+        // Close shortcuts are only registered while their window is focused, so they may be shared
+        // between windows but never with a toggle shortcut
+        if let Some(close_shortcut) = window_config.window_close_shortcut() {
+            let owner = std::iter::once(window_config)
+                .chain(self.windows.iter().flatten())
+                .find(|window| {
+                    window.all_shortcuts().iter().any(|s| same_accelerator(s, &close_shortcut))
+                        || window.shortcut_sequence.iter().flatten().any(|step| same_accelerator(step, &close_shortcut))
+                });
+            if let Some(owner) = owner {
+                return Err(Error::ShortcutConflict(format!(
+                    "close shortcut {} of window {} is already used by window {}",
+                    close_shortcut, window_config.label, owner.label,
+                )));
+            }
+            if self.global_toggle_shortcut.as_deref().map_or(false, |s| same_accelerator(s, &close_shortcut)) {
+                return Err(Error::ShortcutConflict(format!(
+                    "close shortcut {} of window {} is already used by the global toggle shortcut",
+                    close_shortcut, window_config.label,
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Fails if the shortcut can't be the global close shortcut because a toggle shortcut or shortcut
+    /// sequence of a window, the global toggle shortcut or a cycle group uses it
+    pub(crate) fn check_global_close_conflict(&self, shortcut: &str) -> Result<(), Error> {
+        let window = self.windows.iter().flatten().find(|window| {
+            window.all_shortcuts().iter().chain(window.shortcut_sequence.iter().flatten()).any(|s| same_accelerator(s, shortcut))
+        });
+        let owner = match window {
+            Some(window) => Some(format!("window {}", window.label)),
+            None => self
+                .global_toggle_shortcut
+                .as_deref()
+                .filter(|s| same_accelerator(s, shortcut))
+                .map(|_| String::from("the global toggle shortcut"))
+                .or_else(|| self.cycle_group(shortcut).map(|group| format!("the cycle group of {}", group.labels.join(", ")))),
+        };
+        match owner {
+            Some(owner) => Err(Error::ShortcutConflict(format!("the global close shortcut {} is already used by {}", shortcut, owner))),
+            None => Ok(()),
+        }
+    }
+
+    /// Checks the whole config up front, returning every problem rather than only the first:
+    /// malformed or empty accelerators, duplicate window labels, shortcuts used more than once,
+    /// empty shortcut sequences and windows linked or docked to themselves
+    pub fn validate(&self) -> Result<(), Vec<ConfigError>> {
+        let mut errors = vec![];
+        for shortcut in self.global_close_shortcut.iter().chain(&self.global_toggle_shortcut) {
+            if let Err(err) = validate_accelerator(shortcut) {
+                errors.push(ConfigError::Global(err));
+            }
+        }
+        if let (Some(toggle), Some(close)) = (&self.global_toggle_shortcut, &self.global_close_shortcut) {
+            if same_accelerator(toggle, close) {
+                errors.push(ConfigError::Global(Error::ShortcutConflict(String::from(
+                    "the global toggle shortcut is already used by the global close shortcut",
+                ))));
+            }
+        }
+        let groups: Vec<&CycleGroup> = self.cycle_groups.iter().flatten().collect();
+        for (i, group) in groups.iter().enumerate() {
+            let describe = || format!("the cycle group of {}", group.labels.join(", "));
+            if let Err(err) = validate_accelerator(&group.shortcut) {
+                errors.push(ConfigError::Global(err));
+            }
+            if group.labels.is_empty() {
+                errors.push(ConfigError::Global(Error::InvalidShortcut(format!("cycle group {} has no windows", group.shortcut))));
+            }
+            let global = [&self.global_close_shortcut, &self.global_toggle_shortcut]
+                .iter()
+                .any(|global| global.as_deref().map_or(false, |s| same_accelerator(s, &group.shortcut)));
+            let earlier = groups[..i].iter().any(|other| same_accelerator(&other.shortcut, &group.shortcut));
+            if global || earlier {
+                errors.push(ConfigError::Global(Error::ShortcutConflict(format!(
+                    "{} of {} is already used by another global shortcut",
+                    group.shortcut,
+                    describe(),
+                ))));
+            }
+        }
+        let mut labels = HashSet::new();
+        for window in self.windows.iter().flatten() {
+            let mut window_errors = vec![];
+            if !labels.insert(window.label.as_str()) {
+                window_errors.push(Error::DuplicateWindowLabel(window.label.clone()));
+            }
+            // `shortcut` may be left empty for windows without one, but not the additional ones
+            if window.shortcuts.iter().flatten().any(String::is_empty) {
+                window_errors.push(Error::InvalidShortcut(format!("an additional shortcut of window {} is empty", window.label)));
+            }
+            if let Err(err) = window.validate_shortcuts() {
+                window_errors.push(err);
+            }
+            if window.shortcut_sequence.as_ref().map_or(false, Vec::is_empty) {
+                window_errors.push(Error::InvalidShortcut(format!("the shortcut sequence of window {} is empty", window.label)));
+            }
+            if let Err(err) = self.check_shortcut_conflict(window) {
+                window_errors.push(err);
+            }
+            if window.linked_windows.iter().flatten().any(|link| *link == window.label) {
+                window_errors.push(Error::InvalidLink(format!("window {} is linked to itself", window.label)));
+            }
+            if self.docked_to_itself(&window.label) {
+                window_errors.push(Error::InvalidLink(format!("window {} is docked to itself", window.label)));
+            }
+            errors.extend(window_errors.into_iter().map(|error| ConfigError::Window { label: window.label.clone(), error }));
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Whether following the anchors of the window leads back to it, which would have the windows
+    /// chase each other
+    fn docked_to_itself(&self, label: &str) -> bool {
+        let windows: Vec<&WindowConfig> = self.windows.iter().flatten().collect();
+        let mut current = label;
+        // Any longer chain goes in circles without passing the window
+        for _ in 0..windows.len() {
+            let anchor = windows
+                .iter()
+                .find(|window| window.label == current)
+                .and_then(|window| window.dock_to.as_ref());
+            match anchor {
+                Some(anchor) if anchor.label == label => return true,
+                Some(anchor) => current = &anchor.label,
+                None => return false,
+            }
+        }
+        false
+    }
+
+    /// Labels of the windows shown and hidden along with the given one, following links of linked
+    /// windows too. Each label is listed once and never the window's own, even if links go in circles
+    pub fn linked_labels(&self, label: &str) -> Vec<String> {
+        let mut linked: Vec<String> = vec![];
+        let mut pending = vec![label.to_string()];
+        while let Some(current) = pending.pop() {
+            let links = self
+                .windows
+                .iter()
+                .flatten()
+                .filter(|window| window.label == current)
+                .flat_map(|window| window.linked_windows.iter().flatten());
+            for link in links {
+                if link != label && !linked.contains(link) {
+                    linked.push(link.clone());
+                    pending.push(link.clone());
+                }
+            }
+        }
+        linked
+    }
+
+    /// Labels of the windows focus may move to without the given one counting it as losing focus:
+    /// those it is linked with either way and those docked to it or it is docked to, following
+    /// their links and docks too. The window's own label is never listed
+    pub fn group_labels(&self, label: &str) -> Vec<String> {
+        let windows: Vec<&WindowConfig> = self.windows.iter().flatten().collect();
+        let neighbors = |current: &str| -> Vec<String> {
+            let mut neighbors = vec![];
+            for window in &windows {
+                let docked = |a: &WindowConfig, b: &str| a.dock_to.as_ref().map_or(false, |dock_to| dock_to.label == b);
+                if window.label == current {
+                    neighbors.extend(window.linked_windows.iter().flatten().cloned());
+                    neighbors.extend(window.dock_to.iter().map(|dock_to| dock_to.label.clone()));
+                } else if window.linked_windows.iter().flatten().any(|link| link == current) || docked(window, current) {
+                    neighbors.push(window.label.clone());
+                }
+            }
+            neighbors
+        };
+        let mut group: Vec<String> = vec![];
+        let mut pending = vec![label.to_string()];
+        while let Some(current) = pending.pop() {
+            for neighbor in neighbors(&current) {
+                if neighbor != label && !group.contains(&neighbor) {
+                    group.push(neighbor.clone());
+                    pending.push(neighbor);
+                }
+            }
+        }
+        group
+    }
+
     pub fn merge(a: &Self, b: &Self) -> Self {
         let mut windows: Vec<WindowConfig> = vec![];
         if let Some(w) = a.windows.clone() {
@@ -28,23 +754,22 @@ impl PluginConfig {
         if let Some(w) = b.windows.clone() {
             for config in w {
                 if !dict.contains_key(&config.label) {
-                    windows.push(WindowConfig {
-                        label: config.label,
-                        shortcut: config.shortcut,
-                        macos_window_level: config.macos_window_level,
-                    });
+                    windows.push(config);
                 }
             }
         }
         Self {
             windows: {
-                if windows.len() == 0 {
+                if windows.is_empty() {
                     None
                 } else {
                     Some(windows)
                 }
             },
             global_close_shortcut: a.global_close_shortcut.clone().or(b.global_close_shortcut.clone()),
+            global_close_scope: a.global_close_scope.or(b.global_close_scope),
+            global_toggle_shortcut: a.global_toggle_shortcut.clone().or(b.global_toggle_shortcut.clone()),
+            cycle_groups: a.cycle_groups.clone().or(b.cycle_groups.clone()),
         }
     }
 }
@@ -53,6 +778,14 @@ impl PluginConfig {
 mod tests {
     use super::WindowConfig;
     use super::PluginConfig;
+    use super::ToggleBehavior;
+    use super::BlurAction;
+    use super::MacosWindowLevel;
+    use super::IdleFade;
+    use super::CloseScope;
+    use super::{DockSide, DockTo};
+    use crate::error::ConfigError;
+    use crate::Error;
 
     #[test]
     fn merge_and_override_default_value() {
@@ -62,10 +795,13 @@ mod tests {
                 WindowConfig {
                     label: String::from("main"),
                     shortcut: String::from("Ctrl+I"),
-                    macos_window_level: None,
+                    ..Default::default()
                 },
             ]),
             global_close_shortcut: Some(String::from("Escape")),
+            global_close_scope: None,
+            global_toggle_shortcut: None,
+            cycle_groups: None,
         };
         let c = PluginConfig::merge(&a, &b);
         assert_eq!(c, b);
@@ -78,20 +814,26 @@ mod tests {
                 WindowConfig {
                     label: String::from("main"),
                     shortcut: String::from("Ctrl+I"),
-                    macos_window_level: None,
+                    ..Default::default()
                 },
             ]),
             global_close_shortcut: None,
+            global_close_scope: None,
+            global_toggle_shortcut: None,
+            cycle_groups: None,
         };
         let b = PluginConfig {
             windows: Some(vec![
                 WindowConfig {
                     label: String::from("foo"),
                     shortcut: String::from("bar"),
-                    macos_window_level: None,
+                    ..Default::default()
                 },
             ]),
             global_close_shortcut: None,
+            global_close_scope: None,
+            global_toggle_shortcut: None,
+            cycle_groups: None,
         };
         let c = PluginConfig::merge(&a, &b);
         assert_eq!(c, PluginConfig {
@@ -99,15 +841,18 @@ mod tests {
                 WindowConfig {
                     label: String::from("main"),
                     shortcut: String::from("Ctrl+I"),
-                    macos_window_level: None,
+                    ..Default::default()
                 },
                 WindowConfig {
                     label: String::from("foo"),
                     shortcut: String::from("bar"),
-                    macos_window_level: None,
+                    ..Default::default()
                 },
             ]),
             global_close_shortcut: None,
+            global_close_scope: None,
+            global_toggle_shortcut: None,
+            cycle_groups: None,
         });
     }
 
@@ -116,12 +861,345 @@ mod tests {
         let a = PluginConfig {
             windows: None,
             global_close_shortcut: Some(String::from("Escape")),
+            global_close_scope: None,
+            global_toggle_shortcut: None,
+            cycle_groups: None,
         };
         let b = PluginConfig {
             windows: None,
             global_close_shortcut: Some(String::from("baz")),
+            global_close_scope: None,
+            global_toggle_shortcut: None,
+            cycle_groups: None,
         };
         let c = PluginConfig::merge(&a, &b);
         assert_eq!(c, a);
     }
+
+    #[test]
+    fn find_shortcut_owner() {
+        let config = PluginConfig {
+            windows: Some(vec![
+                WindowConfig {
+                    label: String::from("main"),
+                    shortcut: String::from("Ctrl+I"),
+                    ..Default::default()
+                },
+            ]),
+            global_close_shortcut: Some(String::from("Escape")),
+            global_close_scope: None,
+            global_toggle_shortcut: None,
+            cycle_groups: None,
+        };
+        assert_eq!(config.shortcut_owner("Ctrl+I", "foo"), Some(String::from("window main")));
+        assert_eq!(config.shortcut_owner("Ctrl+I", "main"), None);
+        assert_eq!(config.shortcut_owner("Escape", "main"), Some(String::from("the global close shortcut")));
+        assert_eq!(config.shortcut_owner("Ctrl+J", "foo"), None);
+        let config = PluginConfig {
+            global_toggle_shortcut: Some(String::from("Ctrl+Space")),
+            cycle_groups: None,
+            ..config
+        };
+        assert_eq!(config.shortcut_owner("Ctrl+Space", "main"), Some(String::from("the global toggle shortcut")));
+    }
+
+    #[test]
+    fn detect_shortcut_conflict() {
+        let config = PluginConfig {
+            windows: Some(vec![
+                WindowConfig {
+                    label: String::from("main"),
+                    shortcut: String::from("Ctrl+I"),
+                    ..Default::default()
+                },
+                WindowConfig {
+                    label: String::from("foo"),
+                    shortcut: String::from("Ctrl+I"),
+                    ..Default::default()
+                },
+                WindowConfig {
+                    label: String::from("bar"),
+                    shortcut: String::from("Escape"),
+                    ..Default::default()
+                },
+            ]),
+            global_close_shortcut: Some(String::from("Escape")),
+            global_close_scope: None,
+            global_toggle_shortcut: None,
+            cycle_groups: None,
+        };
+        let windows = config.windows.clone().unwrap();
+        match config.check_shortcut_conflict(&windows[1]) {
+            Err(Error::ShortcutConflict(message)) => assert_eq!(message, "Ctrl+I of window foo is already used by window main"),
+            result => panic!("unexpected result: {:?}", result),
+        }
+        assert!(matches!(config.check_shortcut_conflict(&windows[2]), Err(Error::ShortcutConflict(_))));
+    }
+
+    #[test]
+    fn detect_conflict_in_additional_shortcuts() {
+        let config = PluginConfig {
+            windows: Some(vec![
+                WindowConfig {
+                    label: String::from("main"),
+                    shortcut: String::from("Ctrl+I"),
+                    shortcuts: Some(vec![String::from("Ctrl+J")]),
+                    ..Default::default()
+                },
+                WindowConfig {
+                    label: String::from("foo"),
+                    shortcuts: Some(vec![String::from("Ctrl+K"), String::from("Ctrl+J")]),
+                    ..Default::default()
+                },
+            ]),
+            global_close_shortcut: None,
+            global_close_scope: None,
+            global_toggle_shortcut: None,
+            cycle_groups: None,
+        };
+        let windows = config.windows.clone().unwrap();
+        assert_eq!(windows[0].all_shortcuts(), vec![String::from("Ctrl+I"), String::from("Ctrl+J")]);
+        assert_eq!(windows[1].all_shortcuts(), vec![String::from("Ctrl+K"), String::from("Ctrl+J")]);
+        match config.check_shortcut_conflict(&windows[1]) {
+            Err(Error::ShortcutConflict(message)) => assert_eq!(message, "Ctrl+J of window foo is already used by window main"),
+            result => panic!("unexpected result: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn detect_close_shortcut_conflict() {
+        let config = PluginConfig {
+            windows: Some(vec![
+                WindowConfig {
+                    label: String::from("main"),
+                    shortcut: String::from("Ctrl+I"),
+                    close_shortcut: Some(String::from("Escape")),
+                    ..Default::default()
+                },
+                WindowConfig {
+                    label: String::from("foo"),
+                    shortcut: String::from("Ctrl+J"),
+                    hide_on_escape: Some(true),
+                    ..Default::default()
+                },
+                WindowConfig {
+                    label: String::from("bar"),
+                    shortcut: String::from("Ctrl+K"),
+                    close_shortcut: Some(String::from("Ctrl+J")),
+                    ..Default::default()
+                },
+            ]),
+            global_close_shortcut: None,
+            global_close_scope: None,
+            global_toggle_shortcut: None,
+            cycle_groups: None,
+        };
+        let windows = config.windows.clone().unwrap();
+        assert!(config.check_shortcut_conflict(&windows[0]).is_ok());
+        match config.check_shortcut_conflict(&windows[1]) {
+            Err(Error::ShortcutConflict(message)) => assert_eq!(message, "Ctrl+J of window foo is already used by the close shortcut of window bar"),
+            result => panic!("unexpected result: {:?}", result),
+        }
+        match config.check_shortcut_conflict(&windows[2]) {
+            Err(Error::ShortcutConflict(message)) => assert_eq!(message, "close shortcut Ctrl+J of window bar is already used by window foo"),
+            result => panic!("unexpected result: {:?}", result),
+        }
+        let window = WindowConfig {
+            label: String::from("baz"),
+            shortcut: String::from("Escape"),
+            ..Default::default()
+        };
+        match config.check_shortcut_conflict(&window) {
+            Err(Error::ShortcutConflict(message)) => assert_eq!(message, "Escape of window baz is already used by the close shortcut of window main"),
+            result => panic!("unexpected result: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn close_scope_defaults_to_all() {
+        let config: PluginConfig = serde_json::from_str(r#"{ "global_close_shortcut": "Escape" }"#).unwrap();
+        assert_eq!(config.close_scope(), CloseScope::All);
+        let config: PluginConfig = serde_json::from_str(r#"{ "global_close_scope": "frontmost" }"#).unwrap();
+        assert_eq!(config.close_scope(), CloseScope::Frontmost);
+    }
+
+    #[test]
+    fn clamp_idle_fade_target() {
+        let config: WindowConfig = serde_json::from_str(r#"{ "label": "hud", "idle_fade": { "after_ms": 5000, "target_opacity": 0.2 } }"#).unwrap();
+        let idle_fade = config.idle_fade.unwrap();
+        assert_eq!(idle_fade.after(), std::time::Duration::from_secs(5));
+        assert_eq!(idle_fade.target(), 0.2);
+        assert_eq!(IdleFade { after_ms: 0, target_opacity: -1.0 }.target(), 0.0);
+        assert_eq!(IdleFade { after_ms: 0, target_opacity: f64::NAN }.target(), 1.0);
+    }
+
+    #[test]
+    fn window_level_by_name_or_number() {
+        let config: WindowConfig = serde_json::from_str(r#"{ "label": "main", "macos_window_level": "floating" }"#).unwrap();
+        assert_eq!(config.macos_window_level.map(|level| level.level()), Some(3));
+        let config: WindowConfig = serde_json::from_str(r#"{ "label": "main", "macos_window_level": "pop_up_menu" }"#).unwrap();
+        assert_eq!(config.macos_window_level.map(|level| level.level()), Some(101));
+        let config: WindowConfig = serde_json::from_str(r#"{ "label": "main", "macos_window_level": 20 }"#).unwrap();
+        assert_eq!(config.macos_window_level, Some(MacosWindowLevel::Level(20)));
+    }
+
+    #[test]
+    fn auto_hide_defaults_to_true() {
+        let config: WindowConfig = serde_json::from_str(r#"{ "label": "main", "shortcut": "Ctrl+I" }"#).unwrap();
+        assert!(config.should_auto_hide());
+        let config: WindowConfig = serde_json::from_str(r#"{ "label": "main", "shortcut": "Ctrl+I", "auto_hide": false }"#).unwrap();
+        assert!(!config.should_auto_hide());
+    }
+
+    #[test]
+    fn only_regular_windows_activate_the_app_by_default() {
+        let config: WindowConfig = serde_json::from_str(r#"{ "label": "main" }"#).unwrap();
+        assert!(!config.should_activate_macos_app());
+        let config: WindowConfig = serde_json::from_str(r#"{ "label": "main", "macos_panel": false }"#).unwrap();
+        assert!(config.should_activate_macos_app());
+        let config: WindowConfig = serde_json::from_str(r#"{ "label": "main", "macos_activate_app": true }"#).unwrap();
+        assert!(config.should_activate_macos_app());
+    }
+
+    #[test]
+    fn toggle_hides_by_default() {
+        let config: WindowConfig = serde_json::from_str(r#"{ "label": "main" }"#).unwrap();
+        assert_eq!(config.toggle_behavior(), ToggleBehavior::HideWhenVisible);
+        let config: WindowConfig = serde_json::from_str(r#"{ "label": "main", "toggle_behavior": "flash_when_focused" }"#).unwrap();
+        assert_eq!(config.toggle_behavior(), ToggleBehavior::FlashWhenFocused);
+    }
+
+    #[test]
+    fn on_blur_overrides_auto_hide() {
+        let config: WindowConfig = serde_json::from_str(r#"{ "label": "main", "auto_hide": false }"#).unwrap();
+        assert_eq!(config.blur_action(), BlurAction::Nothing);
+        let config: WindowConfig = serde_json::from_str(r#"{ "label": "main", "auto_hide": true, "on_blur": { "dim": 0.4 } }"#).unwrap();
+        assert_eq!(config.blur_action(), BlurAction::Dim(0.4));
+        assert!(!config.should_auto_hide());
+        let config: WindowConfig = serde_json::from_str(r#"{ "label": "main", "auto_hide": false, "on_blur": "hide" }"#).unwrap();
+        assert!(config.should_auto_hide());
+    }
+
+    #[test]
+    fn global_close_shortcut_conflicts_with_toggle_shortcuts() {
+        let config: PluginConfig = serde_json::from_str(r#"{
+            "windows": [{ "label": "main", "shortcut": "Ctrl+I", "shortcut_sequence": ["Ctrl+K", "G"] }],
+            "global_close_shortcut": "Escape",
+            "global_toggle_shortcut": "Ctrl+Shift+Space"
+        }"#).unwrap();
+        assert!(config.check_global_close_conflict("Escape").is_ok());
+        assert!(config.check_global_close_conflict("Ctrl+J").is_ok());
+        for shortcut in ["Ctrl+I", "G", "Ctrl+Shift+Space"] {
+            match config.check_global_close_conflict(shortcut) {
+                Err(Error::ShortcutConflict(_)) => {}
+                result => panic!("unexpected result for {}: {:?}", shortcut, result),
+            }
+        }
+    }
+
+    #[test]
+    fn shortcuts_are_global_by_default() {
+        let config: WindowConfig = serde_json::from_str(r#"{ "label": "main" }"#).unwrap();
+        assert!(config.is_global());
+        let config: WindowConfig = serde_json::from_str(r#"{ "label": "main", "global": false }"#).unwrap();
+        assert!(!config.is_global());
+    }
+
+    #[test]
+    fn outside_click_replaces_auto_hide() {
+        let config: WindowConfig = serde_json::from_str(r#"{ "label": "main", "hide_on_outside_click": true }"#).unwrap();
+        assert!(config.should_hide_on_outside_click());
+        assert!(!config.should_auto_hide());
+        let config: WindowConfig = serde_json::from_str(r#"{ "label": "main", "hide_on_outside_click": true, "auto_hide": true }"#).unwrap();
+        assert!(config.should_auto_hide());
+    }
+
+    #[test]
+    fn validate_reports_every_problem() {
+        let config = PluginConfig {
+            windows: Some(vec![
+                WindowConfig {
+                    label: String::from("main"),
+                    shortcut: String::from("Ctrl+I"),
+                    linked_windows: Some(vec![String::from("main")]),
+                    ..Default::default()
+                },
+                WindowConfig {
+                    label: String::from("main"),
+                    shortcuts: Some(vec![String::new()]),
+                    shortcut_sequence: Some(vec![]),
+                    ..Default::default()
+                },
+                WindowConfig {
+                    label: String::from("foo"),
+                    shortcut: String::from("Ctrl+I"),
+                    dock_to: Some(DockTo { label: String::from("bar"), side: DockSide::Right, gap: None }),
+                    ..Default::default()
+                },
+                WindowConfig {
+                    label: String::from("bar"),
+                    dock_to: Some(DockTo { label: String::from("foo"), side: DockSide::Below, gap: None }),
+                    ..Default::default()
+                },
+            ]),
+            global_close_shortcut: Some(String::from("Ctrl+K")),
+            global_close_scope: None,
+            global_toggle_shortcut: Some(String::from("Ctrl+K")),
+            cycle_groups: None,
+        };
+        let errors = config.validate().unwrap_err();
+        let labels: Vec<_> = errors
+            .iter()
+            .map(|err| match err {
+                ConfigError::Window { label, .. } => label.as_str(),
+                ConfigError::Global(_) => "",
+            })
+            .collect();
+        assert_eq!(labels, ["", "main", "main", "main", "main", "main", "foo", "foo", "bar"]);
+        assert!(matches!(&errors[2], ConfigError::Window { error: Error::InvalidLink(_), .. }));
+        assert!(matches!(&errors[3], ConfigError::Window { error: Error::DuplicateWindowLabel(_), .. }));
+        assert!(matches!(&errors[8], ConfigError::Window { error: Error::InvalidLink(_), .. }));
+        assert!(PluginConfig::default().validate().is_ok());
+    }
+
+    #[test]
+    fn follow_linked_windows_in_circles() {
+        let window = |label: &str, linked: &[&str]| WindowConfig {
+            label: label.to_string(),
+            linked_windows: Some(linked.iter().map(|label| label.to_string()).collect()),
+            ..Default::default()
+        };
+        let config = PluginConfig {
+            windows: Some(vec![window("main", &["results"]), window("results", &["main", "preview"]), window("preview", &["results"])]),
+            ..Default::default()
+        };
+        assert_eq!(config.linked_labels("main"), vec!["results", "preview"]);
+        assert_eq!(config.linked_labels("preview"), vec!["results", "main"]);
+        assert!(config.linked_labels("other").is_empty());
+    }
+
+    #[test]
+    fn group_windows_linked_or_docked_either_way() {
+        let docked = |label: &str, anchor: &str| WindowConfig {
+            label: label.to_string(),
+            dock_to: Some(DockTo { label: anchor.to_string(), side: DockSide::Right, gap: None }),
+            ..Default::default()
+        };
+        let config = PluginConfig {
+            windows: Some(vec![
+                WindowConfig {
+                    label: String::from("main"),
+                    linked_windows: Some(vec![String::from("results")]),
+                    ..Default::default()
+                },
+                WindowConfig { label: String::from("results"), ..Default::default() },
+                docked("preview", "results"),
+                WindowConfig { label: String::from("other"), ..Default::default() },
+            ]),
+            ..Default::default()
+        };
+        assert_eq!(config.group_labels("main"), vec!["results", "preview"]);
+        assert_eq!(config.group_labels("preview"), vec!["results", "main"]);
+        assert!(config.group_labels("other").is_empty());
+    }
 }
@@ -0,0 +1,63 @@
+use tauri::{PhysicalPosition, Window, WindowEvent, Wry};
+use crate::{animation, Error, ManagerExt};
+use crate::position::{constrain_to_monitors, Rect};
+
+/// Returns where the window goes if it is put at `position`, moved onto the monitors if it would
+/// end up off them
+pub(crate) fn constrain_position(window: &Window<Wry>, position: PhysicalPosition<i32>) -> Result<PhysicalPosition<i32>, Error> {
+    let size = window.outer_size().map_err(Error::position_failed("failed to get window size"))?;
+    let monitors: Vec<Rect> = window
+        .available_monitors()
+        .map_err(Error::MonitorQueryFailed)?
+        .iter()
+        .map(|monitor| Rect {
+            x: monitor.position().x,
+            y: monitor.position().y,
+            width: monitor.size().width as i32,
+            height: monitor.size().height as i32,
+        })
+        .collect();
+    let window_rect = Rect {
+        x: position.x,
+        y: position.y,
+        width: size.width as i32,
+        height: size.height as i32,
+    };
+    let (x, y) = constrain_to_monitors(window_rect, &monitors);
+    Ok(PhysicalPosition { x, y })
+}
+
+/// Moves the window back onto the monitors if it was dragged off them
+fn constrain_window(window: &Window<Wry>) -> Result<(), Error> {
+    let position = window.outer_position()?;
+    let constrained = constrain_position(window, position)?;
+    // Moving the window fires another event, which finds it in place
+    if constrained != position {
+        window.set_position(constrained)?;
+    }
+    Ok(())
+}
+
+/// Keeps the window on the monitors every time it is moved, for as long as it is a spotlight
+/// window constrained to its monitor
+pub(crate) fn constrain_window_to_monitors(window: &Window<Wry>) {
+    let w = window.to_owned();
+    window.on_window_event(move |event| {
+        if let WindowEvent::Moved(_) = event {
+            let manager = w.spotlight();
+            let constrain = manager.is_registered(w.label())
+                && manager
+                    .get_window_config(&w)
+                    .ok()
+                    .flatten()
+                    .map_or(false, |window_config| window_config.should_constrain_to_monitor());
+            // Animations move the window on their own and put it back where it was
+            if !constrain || animation::is_animating(&w) {
+                return;
+            }
+            if let Err(err) = constrain_window(&w) {
+                log_warn!("failed to keep spotlight window {} on screen: {}", w.label(), err);
+            }
+        }
+    });
+}
@@ -0,0 +1,74 @@
+use std::{fmt, sync::{Mutex, RwLock}};
+use tauri::{PhysicalPosition, Window, Wry};
+use crate::Error;
+
+type PositionFn = Box<dyn Fn(&Window<Wry>) -> Option<PhysicalPosition<i32>> + Send + Sync>;
+type NextPositions = Vec<(String, PhysicalPosition<i32>)>;
+
+/// Positioning logic of the app that takes precedence over the configured position
+#[derive(Default)]
+pub(crate) struct CustomPosition {
+    position_fn: RwLock<Option<PositionFn>>,
+    /// Where each window is shown the next time, once
+    next_positions: Mutex<NextPositions>,
+}
+
+impl fmt::Debug for CustomPosition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CustomPosition").finish_non_exhaustive()
+    }
+}
+
+impl CustomPosition {
+    /// Replaces the positioning logic, there is only one
+    pub(crate) fn set(&self, position_fn: PositionFn) -> Result<(), Error> {
+        let mut current = self
+            .position_fn
+            .write()
+            .map_err(|_| Error::RwLock(String::from("failed to write position fn")))?;
+        *current = Some(position_fn);
+        Ok(())
+    }
+
+    /// Returns where the window should be shown, `None` leaves it to the configured position
+    pub(crate) fn resolve(&self, window: &Window<Wry>) -> Result<Option<PhysicalPosition<i32>>, Error> {
+        let position_fn = self
+            .position_fn
+            .read()
+            .map_err(|_| Error::RwLock(String::from("failed to read position fn")))?;
+        Ok(position_fn.as_ref().and_then(|position_fn| position_fn(window)))
+    }
+
+    /// Shows the window at `position` the next time it is shown, replacing an earlier one
+    pub(crate) fn set_next(&self, label: &str, position: PhysicalPosition<i32>) -> Result<(), Error> {
+        let mut next_positions = self.lock_next_positions()?;
+        next_positions.retain(|(l, _)| l != label);
+        next_positions.push((label.to_string(), position));
+        Ok(())
+    }
+
+    /// Returns where the window is shown this time, if `set_next` was called since it was last shown
+    pub(crate) fn take_next(&self, label: &str) -> Result<Option<PhysicalPosition<i32>>, Error> {
+        let mut next_positions = self.lock_next_positions()?;
+        Ok(next_positions
+            .iter()
+            .position(|(l, _)| l == label)
+            .map(|i| next_positions.remove(i).1))
+    }
+
+    /// Returns where the window is shown next time without using it up
+    pub(crate) fn next(&self, label: &str) -> Result<Option<PhysicalPosition<i32>>, Error> {
+        Ok(self.lock_next_positions()?.iter().find(|(l, _)| l == label).map(|(_, position)| *position))
+    }
+
+    pub(crate) fn forget(&self, label: &str) -> Result<(), Error> {
+        self.lock_next_positions()?.retain(|(l, _)| l != label);
+        Ok(())
+    }
+
+    fn lock_next_positions(&self) -> Result<std::sync::MutexGuard<'_, NextPositions>, Error> {
+        self.next_positions
+            .lock()
+            .map_err(|_| Error::Mutex(String::from("failed to lock next positions")))
+    }
+}
@@ -0,0 +1,132 @@
+use std::{collections::HashMap, sync::Mutex};
+use crate::{CycleWrap, Error};
+use crate::shortcut_backend::{ShortcutBackend, ShortcutHandler};
+use crate::shortcut_holders::ShortcutHolders;
+
+/// What pressing the shortcut of a cycle group does, by index into the windows of the group
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CycleStep {
+    /// None of the windows is showing, the first one is shown
+    Show(usize),
+    /// The showing window makes way for the next one
+    Advance { hide: usize, show: usize },
+    /// The last window was showing, every window of the group is hidden
+    HideAll,
+}
+
+/// Keeps track of the window each cycle group showed last, and of the windows holding the
+/// shortcut of each group
+#[derive(Default, Debug)]
+pub(crate) struct CycleGroups {
+    /// Index of the window each group showed last, by shortcut
+    current: Mutex<HashMap<String, usize>>,
+    holders: Mutex<HashMap<String, ShortcutHolders>>,
+}
+
+impl CycleGroups {
+    /// Moves the group on to its next window. `showing` tells whether the window at an index is
+    /// showing, a group whose window was hidden in the meantime, e.g. on blur, starts over. A group
+    /// of a single window toggles it
+    pub(crate) fn advance<F>(&self, shortcut: &str, len: usize, wrap: CycleWrap, showing: F) -> Result<Option<CycleStep>, Error>
+    where
+        F: Fn(usize) -> bool,
+    {
+        if len == 0 {
+            return Ok(None);
+        }
+        let mut current = self
+            .current
+            .lock()
+            .map_err(|_| Error::Mutex(String::from("failed to lock cycle groups")))?;
+        let step = match current.get(shortcut).copied().filter(|&i| i < len && showing(i)) {
+            None => CycleStep::Show(0),
+            Some(i) if i + 1 < len => CycleStep::Advance { hide: i, show: i + 1 },
+            Some(i) if wrap == CycleWrap::ShowFirst && len > 1 => CycleStep::Advance { hide: i, show: 0 },
+            Some(_) => CycleStep::HideAll,
+        };
+        match step {
+            CycleStep::Show(i) | CycleStep::Advance { show: i, .. } => current.insert(shortcut.to_string(), i),
+            CycleStep::HideAll => current.remove(shortcut),
+        };
+        Ok(Some(step))
+    }
+
+    /// Holds the shortcut of the group for the window, registering it unless another window of the
+    /// group already holds it
+    pub(crate) fn acquire_shortcut<B: ShortcutBackend>(&self, backend: &mut B, label: &str, shortcut: &str, handler: ShortcutHandler) -> Result<(), Error> {
+        let mut holders = self
+            .holders
+            .lock()
+            .map_err(|_| Error::Mutex(String::from("failed to lock cycle group holders")))?;
+        holders.entry(shortcut.to_string()).or_default().acquire_shortcut(backend, label, shortcut, handler)
+    }
+
+    /// Lets go of the shortcut of the group for the window, unregistering it and forgetting where
+    /// the group is if no other window of the group holds it
+    pub(crate) fn release_shortcut<B: ShortcutBackend>(&self, backend: &mut B, label: &str, shortcut: &str) -> Result<(), Error> {
+        let mut holders = self
+            .holders
+            .lock()
+            .map_err(|_| Error::Mutex(String::from("failed to lock cycle group holders")))?;
+        let group_holders = match holders.get(shortcut) {
+            Some(group_holders) => group_holders,
+            None => return Ok(()),
+        };
+        group_holders.release_shortcut(backend, label, shortcut)?;
+        if !backend.is_registered(shortcut).map_err(Error::query_failed(shortcut))? {
+            holders.remove(shortcut);
+            if let Ok(mut current) = self.current.lock() {
+                current.remove(shortcut);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CycleGroups, CycleStep};
+    use crate::shortcut_backend::mock::MockShortcuts;
+    use crate::CycleWrap;
+
+    #[test]
+    fn cycle_through_windows_and_wrap() {
+        let groups = CycleGroups::default();
+        let advance = |showing: Option<usize>, wrap| groups.advance("F1", 3, wrap, |i| Some(i) == showing).unwrap();
+        assert_eq!(advance(None, CycleWrap::ShowFirst), Some(CycleStep::Show(0)));
+        assert_eq!(advance(Some(0), CycleWrap::ShowFirst), Some(CycleStep::Advance { hide: 0, show: 1 }));
+        assert_eq!(advance(Some(1), CycleWrap::ShowFirst), Some(CycleStep::Advance { hide: 1, show: 2 }));
+        assert_eq!(advance(Some(2), CycleWrap::ShowFirst), Some(CycleStep::Advance { hide: 2, show: 0 }));
+        assert_eq!(advance(Some(0), CycleWrap::HideAll), Some(CycleStep::Advance { hide: 0, show: 1 }));
+        assert_eq!(advance(Some(1), CycleWrap::HideAll), Some(CycleStep::Advance { hide: 1, show: 2 }));
+        assert_eq!(advance(Some(2), CycleWrap::HideAll), Some(CycleStep::HideAll));
+        assert_eq!(advance(None, CycleWrap::HideAll), Some(CycleStep::Show(0)));
+    }
+
+    #[test]
+    fn start_over_once_window_hid_on_its_own() {
+        let groups = CycleGroups::default();
+        groups.advance("F1", 3, CycleWrap::ShowFirst, |_| false).unwrap();
+        groups.advance("F1", 3, CycleWrap::ShowFirst, |i| i == 0).unwrap();
+        assert_eq!(groups.advance("F1", 3, CycleWrap::ShowFirst, |_| false).unwrap(), Some(CycleStep::Show(0)));
+        // A single window toggles
+        assert_eq!(groups.advance("F2", 1, CycleWrap::ShowFirst, |_| false).unwrap(), Some(CycleStep::Show(0)));
+        assert_eq!(groups.advance("F2", 1, CycleWrap::ShowFirst, |_| true).unwrap(), Some(CycleStep::HideAll));
+        assert_eq!(groups.advance("F3", 0, CycleWrap::ShowFirst, |_| true).unwrap(), None);
+    }
+
+    #[test]
+    fn keep_group_shortcut_until_last_window_releases_it() {
+        let groups = CycleGroups::default();
+        let mut shortcuts = MockShortcuts::default();
+        groups.acquire_shortcut(&mut shortcuts, "a", "F1", Box::new(|| {})).unwrap();
+        groups.acquire_shortcut(&mut shortcuts, "b", "F1", Box::new(|| {})).unwrap();
+        groups.advance("F1", 2, CycleWrap::ShowFirst, |_| false).unwrap();
+        groups.release_shortcut(&mut shortcuts, "a", "F1").unwrap();
+        assert!(shortcuts.registered.contains("F1"));
+        groups.release_shortcut(&mut shortcuts, "b", "F1").unwrap();
+        assert!(shortcuts.registered.is_empty());
+        // Where the group was is forgotten along with the shortcut
+        assert_eq!(groups.advance("F1", 2, CycleWrap::ShowFirst, |i| i == 0).unwrap(), Some(CycleStep::Show(0)));
+    }
+}
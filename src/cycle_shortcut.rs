@@ -0,0 +1,78 @@
+use tauri::{AppHandle, Manager, Window, Wry};
+use crate::{animation, Error, ManagerExt};
+use crate::cycle::CycleStep;
+use crate::shortcut_backend::shortcut_backend;
+
+/// Holds the shortcut of every cycle group the window is in, except the skipped ones, for as long
+/// as it is a spotlight window
+pub(crate) fn register_cycle_shortcuts(window: &Window<Wry>, skipped: &[String]) -> Result<(), Error> {
+    let app_handle = window.app_handle();
+    let manager = app_handle.spotlight();
+    if manager.shortcuts_suspended() {
+        return Ok(());
+    }
+    for group in manager.config()?.cycle_groups.iter().flatten() {
+        if !group.labels.iter().any(|label| label == window.label()) || skipped.contains(&group.shortcut) {
+            continue;
+        }
+        let handle = app_handle.clone();
+        let shortcut = group.shortcut.clone();
+        manager.cycle_groups().acquire_shortcut(
+            &mut shortcut_backend(&app_handle),
+            window.label(),
+            &group.shortcut,
+            Box::new(move || {
+                if let Err(err) = cycle(&handle, &shortcut) {
+                    log_error!("failed to cycle spotlight windows with {}: {}", shortcut, err);
+                }
+            }),
+        )?;
+    }
+    Ok(())
+}
+
+pub(crate) fn unregister_cycle_shortcuts(window: &Window<Wry>) -> Result<(), Error> {
+    let app_handle = window.app_handle();
+    let manager = app_handle.spotlight();
+    for group in manager.config()?.cycle_groups.iter().flatten() {
+        if group.labels.iter().any(|label| label == window.label()) {
+            manager
+                .cycle_groups()
+                .release_shortcut(&mut shortcut_backend(&app_handle), window.label(), &group.shortcut)?;
+        }
+    }
+    Ok(())
+}
+
+/// Shows the next window of the group, hiding the one it showed before
+fn cycle(app_handle: &AppHandle<Wry>, shortcut: &str) -> Result<(), Error> {
+    let manager = app_handle.spotlight();
+    let config = manager.config()?;
+    let group = match config.cycle_group(shortcut) {
+        Some(group) => group,
+        None => return Ok(()),
+    };
+    let mut windows = vec![];
+    for label in &group.labels {
+        match app_handle.get_window(label) {
+            Some(window) if manager.is_registered(label) => windows.push(window),
+            // Windows that don't exist yet, or anymore, are left out of the cycle
+            _ => log_debug!("cycle group {} skips {}, which isn't a registered spotlight window", shortcut, label),
+        }
+    }
+    let showing = |i: usize| windows[i].is_visible().unwrap_or(false) && !animation::is_hiding(&windows[i]);
+    match manager.cycle_groups().advance(shortcut, windows.len(), group.wrap(), showing)? {
+        Some(CycleStep::Show(i)) => manager.show(&windows[i]),
+        Some(CycleStep::Advance { hide, show }) => {
+            manager.hide(&windows[hide])?;
+            manager.show(&windows[show])
+        }
+        Some(CycleStep::HideAll) => {
+            for window in &windows {
+                manager.hide(window)?;
+            }
+            Ok(())
+        }
+        None => Ok(()),
+    }
+}
@@ -0,0 +1,119 @@
+use std::{collections::HashMap, sync::Mutex, time::{Duration, Instant}};
+use crate::Error;
+
+/// Remembers when each window was last toggled, so that toggles right after it can be dropped
+#[derive(Default, Debug)]
+pub(crate) struct ToggleDebouncer {
+    last_toggles: Mutex<HashMap<String, Instant>>,
+}
+
+impl ToggleDebouncer {
+    /// Returns `false` if the window was toggled less than `debounce` ago, otherwise records the
+    /// toggle and returns `true`
+    pub(crate) fn accept(&self, label: &str, debounce: Duration, now: Instant) -> Result<bool, Error> {
+        let mut last_toggles = self
+            .last_toggles
+            .lock()
+            .map_err(|_| Error::Mutex(String::from("failed to lock last toggles")))?;
+        if let Some(last_toggle) = last_toggles.get(label) {
+            if now.saturating_duration_since(*last_toggle) < debounce {
+                return Ok(false);
+            }
+        }
+        last_toggles.insert(label.to_string(), now);
+        Ok(true)
+    }
+}
+
+/// Remembers when each window was last shown, so that it isn't hidden again by focus loss right
+/// after it, e.g. by a stray focus event of the app that was in front
+#[derive(Default, Debug)]
+pub(crate) struct ShowCooldown {
+    last_shows: Mutex<HashMap<String, Instant>>,
+}
+
+impl ShowCooldown {
+    pub(crate) fn record(&self, label: &str, now: Instant) -> Result<(), Error> {
+        let mut last_shows = self
+            .last_shows
+            .lock()
+            .map_err(|_| Error::Mutex(String::from("failed to lock last shows")))?;
+        last_shows.insert(label.to_string(), now);
+        Ok(())
+    }
+
+    /// Returns `true` if the window was shown at least once
+    pub(crate) fn shown_before(&self, label: &str) -> Result<bool, Error> {
+        let last_shows = self
+            .last_shows
+            .lock()
+            .map_err(|_| Error::Mutex(String::from("failed to lock last shows")))?;
+        Ok(last_shows.contains_key(label))
+    }
+
+    /// Returns `true` if the window was shown less than `cooldown` ago
+    pub(crate) fn cooling_down(&self, label: &str, cooldown: Duration, now: Instant) -> Result<bool, Error> {
+        let last_shows = self
+            .last_shows
+            .lock()
+            .map_err(|_| Error::Mutex(String::from("failed to lock last shows")))?;
+        Ok(last_shows
+            .get(label)
+            .map_or(false, |last_show| now.saturating_duration_since(*last_show) < cooldown))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+    use super::{ShowCooldown, ToggleDebouncer};
+
+    #[test]
+    fn drop_toggles_within_debounce() {
+        let debouncer = ToggleDebouncer::default();
+        let debounce = Duration::from_millis(150);
+        let start = Instant::now();
+        assert!(debouncer.accept("main", debounce, start).unwrap());
+        assert!(!debouncer.accept("main", debounce, start + Duration::from_millis(100)).unwrap());
+        // Dropped toggles don't extend the debounce
+        assert!(debouncer.accept("main", debounce, start + Duration::from_millis(150)).unwrap());
+    }
+
+    #[test]
+    fn debounce_each_window_separately() {
+        let debouncer = ToggleDebouncer::default();
+        let debounce = Duration::from_millis(150);
+        let start = Instant::now();
+        assert!(debouncer.accept("main", debounce, start).unwrap());
+        assert!(debouncer.accept("secondary", debounce, start).unwrap());
+    }
+
+    #[test]
+    fn zero_debounce_accepts_everything() {
+        let debouncer = ToggleDebouncer::default();
+        let start = Instant::now();
+        assert!(debouncer.accept("main", Duration::ZERO, start).unwrap());
+        assert!(debouncer.accept("main", Duration::ZERO, start).unwrap());
+    }
+
+    #[test]
+    fn cool_down_after_show() {
+        let cooldown = ShowCooldown::default();
+        let duration = Duration::from_millis(200);
+        let start = Instant::now();
+        assert!(!cooldown.cooling_down("main", duration, start).unwrap());
+        cooldown.record("main", start).unwrap();
+        assert!(cooldown.cooling_down("main", duration, start + Duration::from_millis(100)).unwrap());
+        assert!(!cooldown.cooling_down("main", duration, start + Duration::from_millis(200)).unwrap());
+        assert!(!cooldown.cooling_down("secondary", duration, start).unwrap());
+    }
+
+    #[test]
+    fn remember_shown_windows() {
+        let cooldown = ShowCooldown::default();
+        assert!(!cooldown.shown_before("main").unwrap());
+        cooldown.record("main", Instant::now()).unwrap();
+        assert!(cooldown.shown_before("main").unwrap());
+        assert!(!cooldown.shown_before("secondary").unwrap());
+    }
+}
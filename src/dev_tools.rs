@@ -0,0 +1,36 @@
+use tauri::{AppHandle, Manager, PhysicalPosition, Wry};
+use crate::{Error, ManagerExt};
+
+/// Distance in logical pixels between the top-left corners of cascaded windows, and of the first
+/// one from the corner of the primary monitor
+const CASCADE_STEP: f64 = 32.0;
+
+/// Shows every registered spotlight window without taking focus, so that none of them hides right
+/// away, and cascades them from the top-left corner of the primary monitor if asked to. Windows
+/// that fail to show are logged and skipped
+pub(crate) fn reveal_all(app_handle: &AppHandle<Wry>, cascade: bool) -> Result<(), Error> {
+    let manager = app_handle.spotlight();
+    let mut step = 1;
+    for window_config in manager.registered_window_configs(app_handle)? {
+        let window = match app_handle.get_window(&window_config.label) {
+            Some(window) => window,
+            None => continue,
+        };
+        if let Err(err) = manager.show_without_focus(&window) {
+            log_warn!("failed to reveal spotlight window {}: {}", window.label(), err);
+            continue;
+        }
+        if !cascade {
+            continue;
+        }
+        if let Some(monitor) = window.primary_monitor().map_err(Error::MonitorQueryFailed)? {
+            let offset = (CASCADE_STEP * step as f64 * monitor.scale_factor()) as i32;
+            window.set_position(PhysicalPosition {
+                x: monitor.position().x + offset,
+                y: monitor.position().y + offset,
+            })?;
+            step += 1;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,85 @@
+use tauri::{Manager, PhysicalPosition, Window, WindowEvent, Wry};
+use crate::{animation, Error, ManagerExt, WindowConfig};
+use crate::position::{dock_beside, Rect};
+
+/// The visible registered spotlight windows docked to the given one
+pub(crate) fn docked_windows(anchor: &Window<Wry>) -> Result<Vec<(Window<Wry>, WindowConfig)>, Error> {
+    let app_handle = anchor.app_handle();
+    let mut windows = vec![];
+    for window_config in app_handle.spotlight().registered_window_configs(&app_handle)? {
+        let docked = window_config.dock_to.as_ref().map_or(false, |dock_to| dock_to.label == anchor.label());
+        if !docked {
+            continue;
+        }
+        if let Some(window) = anchor.get_window(&window_config.label) {
+            if window.is_visible().map_err(|_| Error::FailedToCheckWindowVisibility)? {
+                windows.push((window, window_config));
+            }
+        }
+    }
+    Ok(windows)
+}
+
+/// Returns where the window goes next to its anchor, or `None` unless it is docked to a visible
+/// spotlight window
+pub(crate) fn docked_position(window: &Window<Wry>, window_config: &WindowConfig) -> Result<Option<PhysicalPosition<i32>>, Error> {
+    let dock_to = match &window_config.dock_to {
+        Some(dock_to) => dock_to,
+        None => return Ok(None),
+    };
+    let anchor = match window.get_window(&dock_to.label) {
+        Some(anchor) if window.spotlight().is_registered(anchor.label()) => anchor,
+        _ => return Ok(None),
+    };
+    if !anchor.is_visible().map_err(|_| Error::FailedToCheckWindowVisibility)? {
+        return Ok(None);
+    }
+    // An anchor that is sliding in is docked to where it ends up
+    let position = match animation::rest_position(&anchor) {
+        Some(position) => position,
+        None => anchor.outer_position().map_err(Error::position_failed("failed to get window position"))?,
+    };
+    let anchor_size = anchor.outer_size().map_err(Error::position_failed("failed to get window size"))?;
+    let size = window.outer_size().map_err(Error::position_failed("failed to get window size"))?;
+    let anchor_rect = Rect {
+        x: position.x,
+        y: position.y,
+        width: anchor_size.width as i32,
+        height: anchor_size.height as i32,
+    };
+    let gap = (dock_to.gap() * anchor.scale_factor().map_err(Error::position_failed("failed to get scale factor"))?).round() as i32;
+    let (x, y) = dock_beside(anchor_rect, size.width as i32, size.height as i32, dock_to.side, gap);
+    Ok(Some(PhysicalPosition { x, y }))
+}
+
+/// Moves the visible windows docked to the anchor next to it. Those that are animating are left
+/// alone, they would be pulled out of their animation
+pub(crate) fn follow_anchor(anchor: &Window<Wry>) -> Result<(), Error> {
+    for (window, window_config) in docked_windows(anchor)? {
+        if animation::is_animating(&window) {
+            continue;
+        }
+        if let Some(position) = docked_position(&window, &window_config)? {
+            if window.outer_position()? != position {
+                window.set_position(position)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Moves the windows docked to the window along every time it is moved, for as long as it is a
+/// spotlight window
+pub(crate) fn keep_docked_windows_next_to(window: &Window<Wry>) {
+    let w = window.to_owned();
+    window.on_window_event(move |event| {
+        if let WindowEvent::Moved(_) = event {
+            if !w.spotlight().is_registered(w.label()) {
+                return;
+            }
+            if let Err(err) = follow_anchor(&w) {
+                log_warn!("failed to move windows docked to spotlight window {}: {}", w.label(), err);
+            }
+        }
+    });
+}
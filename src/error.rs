@@ -2,18 +2,109 @@
 pub enum Error {
     #[error("failed to get NSWindow")]
     FailedToGetNSWindow,
+    #[error("failed to get native window handle")]
+    FailedToGetWindowHandle,
     #[error("failed to get NSObject class")]
     FailedToCheckWindowVisibility,
     #[error("failed to hide window")]
     FailedToHideWindow,
     #[error("failed to show window")]
     FailedToShowWindow,
+    #[error("failed to focus window")]
+    FailedToFocusWindow,
     #[error("tauri err: {0}")]
     Tauri(#[from] tauri::Error),
+    #[error("io: {0}")]
+    Io(#[from] std::io::Error),
     #[error("rwLock: {0}")]
     RwLock(String),
     #[error("mutex: {0}")]
     Mutex(String),
+    #[error("invalid shortcut: {0}")]
+    InvalidShortcut(String),
+    #[error("failed to query whether shortcut {accelerator} is registered: {source}")]
+    ShortcutQueryFailed { accelerator: String, source: tauri::Error },
+    #[error("failed to register shortcut {accelerator}: {source}")]
+    ShortcutRegisterFailed { accelerator: String, source: tauri::Error },
+    #[error("failed to unregister shortcut {accelerator}: {source}")]
+    ShortcutUnregisterFailed { accelerator: String, source: tauri::Error },
+    #[error("shortcut conflict: {0}")]
+    ShortcutConflict(String),
+    #[error("duplicate spotlight window label: {0}")]
+    DuplicateWindowLabel(String),
+    #[error("window is not a registered spotlight window: {0}")]
+    UnregisteredWindow(String),
+    #[error("window is already a registered spotlight window: {0}")]
+    AlreadyRegistered(String),
+    #[error("window not found: {0}")]
+    WindowNotFound(String),
+    #[error("monitor index {0} is out of range, there are {1} monitors")]
+    MonitorIndexOutOfRange(usize, usize),
+    #[error("failed to query monitors: {0}")]
+    MonitorQueryFailed(tauri::Error),
+    #[error("failed to resolve window position: {0}")]
+    PositionResolutionFailed(String),
+    #[error("background effect is not supported on this platform: {0}")]
+    UnsupportedBackgroundEffect(String),
+    #[error("failed for spotlight windows: {}", describe_window_errors(.0))]
+    WindowsFailed(Vec<(String, Error)>),
+    #[error("window opacity is not supported on this platform")]
+    UnsupportedOpacity,
+    #[error("invalid linked window: {0}")]
+    InvalidLink(String),
     #[error("other: {0}")]
     Other(String),
 }
+
+impl Error {
+    /// Wraps a failure to query whether the accelerator is registered, for use with `map_err`
+    pub(crate) fn query_failed<E: Into<tauri::Error>>(accelerator: &str) -> impl FnOnce(E) -> Self + '_ {
+        move |source| Error::ShortcutQueryFailed { accelerator: accelerator.to_string(), source: source.into() }
+    }
+
+    /// Wraps a failure to work out where the window goes, for use with `map_err`
+    pub(crate) fn position_failed<E: std::fmt::Display>(what: &str) -> impl FnOnce(E) -> Self + '_ {
+        move |source| Error::PositionResolutionFailed(format!("{}: {}", what, source))
+    }
+
+    /// Wraps a failure to register the accelerator, for use with `map_err`
+    pub(crate) fn register_failed<E: Into<tauri::Error>>(accelerator: &str) -> impl FnOnce(E) -> Self + '_ {
+        move |source| Error::ShortcutRegisterFailed { accelerator: accelerator.to_string(), source: source.into() }
+    }
+
+    pub(crate) fn unregister_failed<E: Into<tauri::Error>>(accelerator: &str) -> impl FnOnce(E) -> Self + '_ {
+        move |source| Error::ShortcutUnregisterFailed { accelerator: accelerator.to_string(), source: source.into() }
+    }
+}
+
+/// A problem found by `PluginConfig::validate`, either with one of the windows or with the
+/// shortcuts shared by all of them
+#[derive(thiserror::Error, Debug)]
+pub enum ConfigError {
+    #[error("{error}")]
+    Window { label: String, error: Error },
+    #[error("{0}")]
+    Global(Error),
+}
+
+impl From<ConfigError> for Error {
+    fn from(err: ConfigError) -> Self {
+        match err {
+            ConfigError::Window { error, .. } | ConfigError::Global(error) => error,
+        }
+    }
+}
+
+fn describe_window_errors(errors: &[(String, Error)]) -> String {
+    errors
+        .iter()
+        .map(|(label, err)| format!("{}: {}", label, err))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+impl serde::Serialize for Error {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.to_string().as_ref())
+    }
+}
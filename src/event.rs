@@ -0,0 +1,94 @@
+use tauri::{AppHandle, Manager, Monitor, Window, Wry};
+use crate::Error;
+
+/// Emitted right before a hidden window is shown, unless showing it is vetoed
+pub const SPOTLIGHT_WILL_SHOW: &str = "spotlight_will_show";
+pub const SPOTLIGHT_DID_SHOW: &str = "spotlight_did_show";
+pub const SPOTLIGHT_DID_HIDE: &str = "spotlight_did_hide";
+pub const WINDOW_DID_RESIGN_KEY: &str = "window_did_resign_key";
+/// Emitted whenever a toggle shortcut or shortcut sequence of a window is pressed, before it is
+/// decided whether the window is shown or hidden
+pub const SPOTLIGHT_SHORTCUT_TRIGGERED: &str = "spotlight_shortcut_triggered";
+/// Emitted instead of hiding a focused window whose toggle behavior is to flash
+pub const SPOTLIGHT_FLASH: &str = "spotlight_flash";
+/// Emitted when a window is pinned open or unpinned
+pub const SPOTLIGHT_PIN_CHANGED: &str = "spotlight_pin_changed";
+/// Emitted when the global close shortcut is pressed, right before the windows are hidden
+pub const SPOTLIGHT_GLOBAL_CLOSE: &str = "spotlight_global_close";
+/// Emitted with the characters typed into a window that captures pending input before any of its
+/// inputs had focus
+pub const SPOTLIGHT_PENDING_INPUT: &str = "spotlight_pending_input";
+/// Emitted when a window would have been shown off screen, e.g. on a monitor that was unplugged,
+/// and is moved to the primary monitor instead
+pub const SPOTLIGHT_MONITOR_FALLBACK: &str = "spotlight_monitor_fallback";
+
+#[derive(serde::Serialize, Debug, Clone, PartialEq)]
+pub struct SpotlightEventPayload {
+    pub label: String,
+}
+
+#[derive(serde::Serialize, Debug, Clone, PartialEq)]
+pub struct SpotlightPinPayload {
+    pub label: String,
+    pub pinned: bool,
+}
+
+#[derive(serde::Serialize, Debug, Clone, PartialEq)]
+pub struct SpotlightPendingInputPayload {
+    pub label: String,
+    pub characters: String,
+}
+
+#[derive(serde::Serialize, Debug, Clone, PartialEq)]
+pub struct SpotlightMonitorFallbackPayload {
+    pub label: String,
+    /// Name of the primary monitor the window was moved to
+    pub monitor: Option<String>,
+}
+
+#[derive(serde::Serialize, Debug, Clone, PartialEq)]
+pub struct SpotlightGlobalClosePayload {
+    /// Labels of the visible spotlight windows about to be hidden
+    pub labels: Vec<String>,
+}
+
+/// Emits a spotlight event about the given window to all windows
+pub(crate) fn emit_spotlight_event(window: &Window<Wry>, event: &str) -> Result<(), Error> {
+    window.app_handle().emit_all(event, SpotlightEventPayload {
+        label: window.label().to_string(),
+    })?;
+    Ok(())
+}
+
+/// Emits the pin state of the window to all windows
+pub(crate) fn emit_pin_changed(window: &Window<Wry>, pinned: bool) -> Result<(), Error> {
+    window.app_handle().emit_all(SPOTLIGHT_PIN_CHANGED, SpotlightPinPayload {
+        label: window.label().to_string(),
+        pinned,
+    })?;
+    Ok(())
+}
+
+/// Emits the characters typed into the window before one of its inputs had focus to all windows
+pub(crate) fn emit_pending_input(window: &Window<Wry>, characters: String) -> Result<(), Error> {
+    window.app_handle().emit_all(SPOTLIGHT_PENDING_INPUT, SpotlightPendingInputPayload {
+        label: window.label().to_string(),
+        characters,
+    })?;
+    Ok(())
+}
+
+/// Emits that the window was moved to the primary monitor to all windows
+pub(crate) fn emit_monitor_fallback(window: &Window<Wry>, monitor: &Monitor) -> Result<(), Error> {
+    window.app_handle().emit_all(SPOTLIGHT_MONITOR_FALLBACK, SpotlightMonitorFallbackPayload {
+        label: window.label().to_string(),
+        monitor: monitor.name().cloned(),
+    })?;
+    Ok(())
+}
+
+/// Emits the global close event to all windows
+pub(crate) fn emit_global_close(app_handle: &AppHandle<Wry>, labels: Vec<String>) -> Result<(), Error> {
+    app_handle.emit_all(SPOTLIGHT_GLOBAL_CLOSE, SpotlightGlobalClosePayload { labels })?;
+    Ok(())
+}
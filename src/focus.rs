@@ -0,0 +1,40 @@
+use tauri::{Window, Wry};
+use crate::Error;
+
+/// How many times the element is looked up again while the page is still rendering
+const FOCUS_ATTEMPTS: u32 = 10;
+const FOCUS_RETRY_INTERVAL_MS: u32 = 50;
+
+/// Focuses the first element in the window matching the selector. Pages that are still loading or
+/// rendering are given a moment for the element to appear, nothing happens if it never does
+pub(crate) fn focus_element(window: &Window<Wry>, selector: &str) -> Result<(), Error> {
+    window.eval(&focus_script(selector, FOCUS_ATTEMPTS))?;
+    Ok(())
+}
+
+fn focus_script(selector: &str, attempts: u32) -> String {
+    // A JSON string is a valid JS string literal, so the selector can't break out of it
+    let selector = serde_json::Value::from(selector).to_string();
+    format!(
+        "(function focus(attempts) {{ \
+            const element = document.querySelector({selector}); \
+            if (element) {{ element.focus(); }} \
+            else if (attempts > 0) {{ setTimeout(() => focus(attempts - 1), {interval}); }} \
+        }})({attempts});",
+        selector = selector,
+        interval = FOCUS_RETRY_INTERVAL_MS,
+        attempts = attempts,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::focus_script;
+
+    #[test]
+    fn quote_selector_in_script() {
+        let script = focus_script(r#"input[name="query"]"#, 3);
+        assert!(script.contains(r#"document.querySelector("input[name=\"query\"]")"#));
+        assert!(script.ends_with("})(3);"));
+    }
+}
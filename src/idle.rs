@@ -0,0 +1,64 @@
+use std::{collections::HashMap, sync::{atomic::{AtomicU64, Ordering}, Mutex}};
+use crate::Error;
+
+/// Tells the timers hiding idle windows apart, so that only the one armed last for a window hides
+/// it and restarting the timer doesn't need to stop the previous one
+#[derive(Default, Debug)]
+pub(crate) struct IdleTimers {
+    armed: Mutex<HashMap<String, u64>>,
+    next_generation: AtomicU64,
+}
+
+impl IdleTimers {
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, HashMap<String, u64>>, Error> {
+        self.armed.lock().map_err(|_| Error::Mutex(String::from("failed to lock idle timers")))
+    }
+
+    /// Arms a new timer for the window, replacing the previous one, and returns its generation
+    pub(crate) fn arm(&self, label: &str) -> Result<u64, Error> {
+        let generation = self.next_generation.fetch_add(1, Ordering::SeqCst);
+        self.lock()?.insert(label.to_string(), generation);
+        Ok(generation)
+    }
+
+    pub(crate) fn disarm(&self, label: &str) -> Result<(), Error> {
+        self.lock()?.remove(label);
+        Ok(())
+    }
+
+    /// Returns `true` if the timer of this generation is still the one armed for the window, in
+    /// which case it is disarmed
+    pub(crate) fn fire(&self, label: &str, generation: u64) -> Result<bool, Error> {
+        let mut armed = self.lock()?;
+        if armed.get(label) != Some(&generation) {
+            return Ok(false);
+        }
+        armed.remove(label);
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::IdleTimers;
+
+    #[test]
+    fn only_last_armed_timer_fires() {
+        let timers = IdleTimers::default();
+        let first = timers.arm("main").unwrap();
+        let second = timers.arm("main").unwrap();
+        assert!(!timers.fire("main", first).unwrap());
+        assert!(timers.fire("main", second).unwrap());
+        assert!(!timers.fire("main", second).unwrap());
+    }
+
+    #[test]
+    fn disarmed_timer_does_not_fire() {
+        let timers = IdleTimers::default();
+        let generation = timers.arm("main").unwrap();
+        let other = timers.arm("secondary").unwrap();
+        timers.disarm("main").unwrap();
+        assert!(!timers.fire("main", generation).unwrap());
+        assert!(timers.fire("secondary", other).unwrap());
+    }
+}
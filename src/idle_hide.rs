@@ -0,0 +1,77 @@
+use std::time::Duration;
+use tauri::{Window, Wry};
+use crate::{animation, Error, ManagerExt};
+
+/// Hides the window once it has been left alone for `auto_hide_after_ms`, and fades it once it has
+/// been left alone for `idle_fade`, starting both over every time this is called. A faded window is
+/// brought back to its full opacity
+pub(crate) fn restart_idle_timer(window: &Window<Wry>) -> Result<(), Error> {
+    restart_idle_fade(window)?;
+    restart_hide_timer(window)
+}
+
+fn restart_hide_timer(window: &Window<Wry>) -> Result<(), Error> {
+    let manager = window.spotlight();
+    let delay = match manager.get_window_config(window)?.and_then(|window_config| window_config.auto_hide_after()) {
+        Some(delay) => delay,
+        None => return Ok(()),
+    };
+    let generation = manager.idle_timers().arm(window.label())?;
+    after_idle(window, delay, "hide", move |window| hide_if_idle(window, generation));
+    Ok(())
+}
+
+fn restart_idle_fade(window: &Window<Wry>) -> Result<(), Error> {
+    animation::end_idle_fade(window)?;
+    let manager = window.spotlight();
+    let delay = match manager.get_window_config(window)?.and_then(|window_config| window_config.idle_fade) {
+        Some(idle_fade) => idle_fade.after(),
+        None => return Ok(()),
+    };
+    let generation = manager.idle_fade_timers().arm(window.label())?;
+    after_idle(window, delay, "fade", move |window| fade_if_idle(window, generation));
+    Ok(())
+}
+
+/// Runs `f` on the main thread once the delay has passed
+fn after_idle<F>(window: &Window<Wry>, delay: Duration, action: &'static str, f: F)
+where
+    F: FnOnce(&Window<Wry>) -> Result<(), Error> + Send + 'static,
+{
+    let w = window.clone();
+    std::thread::spawn(move || {
+        std::thread::sleep(delay);
+        let window = w.clone();
+        let result = w.run_on_main_thread(move || {
+            if let Err(err) = f(&window) {
+                log_error!("failed to {} idle spotlight window {}: {}", action, window.label(), err);
+            }
+        });
+        if let Err(err) = result {
+            log_error!("failed to {} idle spotlight window {}: {}", action, w.label(), err);
+        }
+    });
+}
+
+fn hide_if_idle(window: &Window<Wry>, generation: u64) -> Result<(), Error> {
+    let manager = window.spotlight();
+    if !manager.idle_timers().fire(window.label(), generation)? {
+        return Ok(());
+    }
+    // Pinned windows and those with auto-hide suspended are checked again later
+    if manager.is_auto_hide_suspended(window.label())? {
+        return restart_hide_timer(window);
+    }
+    manager.hide(window)
+}
+
+fn fade_if_idle(window: &Window<Wry>, generation: u64) -> Result<(), Error> {
+    let manager = window.spotlight();
+    if !manager.idle_fade_timers().fire(window.label(), generation)? {
+        return Ok(());
+    }
+    match manager.get_window_config(window)?.and_then(|window_config| window_config.idle_fade) {
+        Some(idle_fade) => animation::fade_idle(window, idle_fade.target()),
+        None => Ok(()),
+    }
+}
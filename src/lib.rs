@@ -1,16 +1,68 @@
+#[macro_use]
+mod logging;
 #[cfg_attr(target_os = "macos", path = "spotlight_macos/mod.rs")]
 #[cfg_attr(not(target_os = "macos"), path = "spotlight_others.rs")]
+mod platform;
 mod spotlight;
 mod error;
 mod config;
+mod event;
+mod position;
+mod accelerator;
+mod builder;
+mod state;
+mod persistence;
+mod animation;
+mod background_effect;
+mod debounce;
+mod window_lock;
+mod shortcut_holders;
+mod show_veto;
+mod focus;
+mod custom_position;
+mod previous_focus;
+mod shortcut_backend;
+mod sequence;
+mod shortcut_sequence;
+mod cycle;
+mod cycle_shortcut;
+mod restore;
+mod constrain;
+mod prevent_close;
+mod dock;
+mod idle;
+mod idle_hide;
+mod linked;
+mod pending_input;
+mod app_focus;
+#[cfg(feature = "dev-tools")]
+mod dev_tools;
+mod registered_windows;
 
-pub use config::{PluginConfig, WindowConfig};
-pub use error::Error;
+pub use builder::{PluginConfigBuilder, WindowConfigBuilder};
+pub use config::{
+    AnimationConfig, BackgroundEffect, BlurAction, CloseScope, CycleGroup, CycleWrap, DockSide, DockTo, HideAnimation,
+    IdleFade, MacosWindowLevel, PluginConfig, PositionPreset, SlideDirection, ToggleBehavior, WindowConfig, WindowPosition,
+    WindowTier,
+};
+pub use accelerator::Accelerator;
+pub use error::{ConfigError, Error};
+pub use state::{SpotlightState, TargetMonitor};
+pub use event::{
+    SpotlightEventPayload, SpotlightGlobalClosePayload, SpotlightMonitorFallbackPayload, SpotlightPendingInputPayload,
+    SpotlightPinPayload, SPOTLIGHT_DID_HIDE, SPOTLIGHT_DID_SHOW, SPOTLIGHT_FLASH, SPOTLIGHT_GLOBAL_CLOSE,
+    SPOTLIGHT_MONITOR_FALLBACK, SPOTLIGHT_PENDING_INPUT, SPOTLIGHT_PIN_CHANGED, SPOTLIGHT_SHORTCUT_TRIGGERED,
+    SPOTLIGHT_WILL_SHOW, WINDOW_DID_RESIGN_KEY,
+};
 
+use std::time::Duration;
 use tauri::{
     plugin::{Builder, TauriPlugin},
-    Manager, Wry, Runtime, State, Window
+    AppHandle, Manager, PhysicalPosition, PhysicalSize, RunEvent, Wry, Runtime, State, Window,
+    WindowEvent,
 };
+use accelerator::validate_accelerator;
+use shortcut_backend::{shortcut_backend, ShortcutBackend};
 
 pub trait ManagerExt<R: Runtime> {
     fn spotlight(&self) -> State<'_, spotlight::SpotlightManager>;
@@ -22,31 +74,267 @@ impl<R: Runtime, T: Manager<R>> ManagerExt<R> for T {
   }
 }
 
+/// Returns the spotlight window with the given label, or the calling window if no label is given
+fn get_spotlight_window(manager: &spotlight::SpotlightManager, window: &Window<Wry>, label: Option<String>) -> Result<Window<Wry>, Error> {
+    let label = label.unwrap_or_else(|| window.label().to_string());
+    if !manager.is_registered(&label) {
+        return Err(Error::UnregisteredWindow(label));
+    }
+    match window.get_window(&label) {
+        Some(window) => Ok(window),
+        None => {
+            log_debug!("forgetting spotlight window {}, which doesn't exist anymore", label);
+            manager.prune_window(&label)?;
+            Err(Error::WindowNotFound(label))
+        }
+    }
+}
+
+#[tauri::command]
+fn show(manager: State<'_, spotlight::SpotlightManager>, window: Window<Wry>, label: Option<String>) -> Result<(), Error> {
+    manager.show(&get_spotlight_window(&manager, &window, label)?)
+}
+
+#[tauri::command]
+fn show_without_focus(manager: State<'_, spotlight::SpotlightManager>, window: Window<Wry>, label: Option<String>) -> Result<(), Error> {
+    manager.show_without_focus(&get_spotlight_window(&manager, &window, label)?)
+}
+
+#[tauri::command]
+fn show_on_monitor(manager: State<'_, spotlight::SpotlightManager>, window: Window<Wry>, label: Option<String>, monitor_index: usize) -> Result<(), Error> {
+    manager.show_on_monitor(&get_spotlight_window(&manager, &window, label)?, monitor_index)
+}
+
+#[tauri::command]
+fn move_to_next_monitor(manager: State<'_, spotlight::SpotlightManager>, window: Window<Wry>, label: Option<String>) -> Result<(), Error> {
+    manager.move_to_next_monitor(&get_spotlight_window(&manager, &window, label)?)
+}
+
+#[tauri::command]
+fn set_next_position(manager: State<'_, spotlight::SpotlightManager>, window: Window<Wry>, label: Option<String>, x: i32, y: i32) -> Result<(), Error> {
+    manager.set_next_position(&get_spotlight_window(&manager, &window, label)?, PhysicalPosition { x, y })
+}
+
+#[tauri::command]
+fn set_show_blocked(manager: State<'_, spotlight::SpotlightManager>, window: Window<Wry>, label: Option<String>, blocked: bool) -> Result<(), Error> {
+    manager.set_show_blocked(&get_spotlight_window(&manager, &window, label)?, blocked)
+}
+
+#[tauri::command]
+fn hide(manager: State<'_, spotlight::SpotlightManager>, window: Window<Wry>, label: Option<String>) -> Result<(), Error> {
+    manager.hide(&get_spotlight_window(&manager, &window, label)?)
+}
+
+#[tauri::command]
+fn hide_all(manager: State<'_, spotlight::SpotlightManager>, app_handle: AppHandle<Wry>) -> Result<(), Error> {
+    manager.hide_all(&app_handle)
+}
+
+#[tauri::command]
+fn show_all(manager: State<'_, spotlight::SpotlightManager>, app_handle: AppHandle<Wry>) -> Result<(), Error> {
+    manager.show_all(&app_handle)
+}
+
+#[tauri::command]
+fn toggle(manager: State<'_, spotlight::SpotlightManager>, window: Window<Wry>, label: Option<String>) -> Result<(), Error> {
+    let window = get_spotlight_window(&manager, &window, label)?;
+    manager.toggle(&window)
+}
+
+#[cfg(feature = "dev-tools")]
+#[tauri::command]
+fn reveal_all_spotlight(app_handle: AppHandle<Wry>, cascade: bool) -> Result<(), Error> {
+    dev_tools::reveal_all(&app_handle, cascade)
+}
+
+#[tauri::command]
+fn get_spotlight_state(manager: State<'_, spotlight::SpotlightManager>, window: Window<Wry>, label: Option<String>) -> Result<SpotlightState, Error> {
+    let window = get_spotlight_window(&manager, &window, label)?;
+    let window_config = manager
+        .get_window_config(&window)?
+        .ok_or_else(|| Error::UnregisteredWindow(window.label().to_string()))?;
+    state::get_spotlight_state(&window, &window_config)
+}
+
+/// Returns the monitor the window would be shown on right now, `None` if it can't be told
+#[tauri::command]
+fn get_target_monitor(manager: State<'_, spotlight::SpotlightManager>, window: Window<Wry>, label: Option<String>) -> Result<Option<TargetMonitor>, Error> {
+    manager.target_monitor(&get_spotlight_window(&manager, &window, label)?)
+}
+
+#[tauri::command]
+fn list_spotlight_windows(manager: State<'_, spotlight::SpotlightManager>, app_handle: AppHandle<Wry>) -> Result<Vec<WindowConfig>, Error> {
+    manager.registered_window_configs(&app_handle)
+}
+
+/// Called by the script capturing pending input, see `pending_input`
+#[tauri::command]
+fn report_pending_input(manager: State<'_, spotlight::SpotlightManager>, window: Window<Wry>, characters: String) -> Result<(), Error> {
+    if !manager.is_registered(window.label()) {
+        return Err(Error::UnregisteredWindow(window.label().to_string()));
+    }
+    event::emit_pending_input(&window, characters)
+}
+
+#[tauri::command]
+fn recenter_spotlight(manager: State<'_, spotlight::SpotlightManager>, window: Window<Wry>, label: Option<String>) -> Result<(), Error> {
+    manager.recenter(&get_spotlight_window(&manager, &window, label)?)
+}
+
+#[tauri::command]
+fn set_spotlight_opacity(manager: State<'_, spotlight::SpotlightManager>, window: Window<Wry>, label: Option<String>, opacity: f64) -> Result<(), Error> {
+    manager.set_opacity(&get_spotlight_window(&manager, &window, label)?, opacity)
+}
+
+#[tauri::command]
+fn suspend_auto_hide(manager: State<'_, spotlight::SpotlightManager>, window: Window<Wry>, label: Option<String>) -> Result<(), Error> {
+    manager.suspend_auto_hide(&get_spotlight_window(&manager, &window, label)?)
+}
+
+#[tauri::command]
+fn resume_auto_hide(manager: State<'_, spotlight::SpotlightManager>, window: Window<Wry>, label: Option<String>) -> Result<(), Error> {
+    manager.resume_auto_hide(&get_spotlight_window(&manager, &window, label)?)
+}
+
+#[tauri::command]
+fn reset_idle_timer(manager: State<'_, spotlight::SpotlightManager>, window: Window<Wry>, label: Option<String>) -> Result<(), Error> {
+    manager.reset_idle_timer(&get_spotlight_window(&manager, &window, label)?)
+}
+
+#[tauri::command]
+fn set_spotlight_auto_hide(manager: State<'_, spotlight::SpotlightManager>, window: Window<Wry>, label: Option<String>, auto_hide: bool) -> Result<(), Error> {
+    manager.set_auto_hide(&get_spotlight_window(&manager, &window, label)?, auto_hide)
+}
+
+#[tauri::command]
+fn set_ignore_cursor_events(manager: State<'_, spotlight::SpotlightManager>, window: Window<Wry>, label: Option<String>, ignore: bool) -> Result<(), Error> {
+    manager.set_ignore_cursor_events(&get_spotlight_window(&manager, &window, label)?, ignore)
+}
+
+#[tauri::command]
+fn set_spotlight_pinned(manager: State<'_, spotlight::SpotlightManager>, window: Window<Wry>, label: Option<String>, pinned: bool) -> Result<(), Error> {
+    manager.set_pinned(&get_spotlight_window(&manager, &window, label)?, pinned)
+}
+
+#[tauri::command]
+fn update_shortcut(manager: State<'_, spotlight::SpotlightManager>, window: Window<Wry>, label: String, shortcut: String) -> Result<(), Error> {
+    validate_accelerator(&shortcut)?;
+    manager.update_shortcut(&get_spotlight_window(&manager, &window, Some(label))?, &shortcut)
+}
+
+#[tauri::command]
+fn set_global_close_shortcut(manager: State<'_, spotlight::SpotlightManager>, app_handle: AppHandle<Wry>, accelerator: Option<String>) -> Result<(), Error> {
+    manager.set_global_close_shortcut(&app_handle, accelerator.as_deref())
+}
+
+#[tauri::command]
+fn suspend_all_shortcuts(manager: State<'_, spotlight::SpotlightManager>, app_handle: AppHandle<Wry>) -> Result<(), Error> {
+    manager.suspend_all_shortcuts(&app_handle)
+}
+
+#[tauri::command]
+fn resume_all_shortcuts(manager: State<'_, spotlight::SpotlightManager>, app_handle: AppHandle<Wry>) -> Result<(), Error> {
+    manager.resume_all_shortcuts(&app_handle)
+}
+
+#[tauri::command]
+fn set_spotlight_size(manager: State<'_, spotlight::SpotlightManager>, window: Window<Wry>, label: Option<String>, width: u32, height: u32) -> Result<(), Error> {
+    manager.set_size(&get_spotlight_window(&manager, &window, label)?, PhysicalSize { width, height })
+}
+
 #[tauri::command]
-fn show(manager: State<'_, spotlight::SpotlightManager>, window: Window<Wry>) -> Result<(), String> {
-    manager.show(&window).map_err(|err| format!("{:?}", err))
+fn animate_height(manager: State<'_, spotlight::SpotlightManager>, window: Window<Wry>, label: Option<String>, target_height: u32, duration_ms: u64) -> Result<(), Error> {
+    manager.animate_height(&get_spotlight_window(&manager, &window, label)?, target_height, Duration::from_millis(duration_ms))
 }
 
 #[tauri::command]
-fn hide(manager: State<'_, spotlight::SpotlightManager>, window: Window<Wry>) -> Result<(), String> {
-    manager.hide(&window).map_err(|err| format!("{:?}", err))
+fn register_spotlight_window(manager: State<'_, spotlight::SpotlightManager>, window: Window<Wry>, window_config: WindowConfig) -> Result<(), Error> {
+    window_config.validate_shortcuts()?;
+    let target = window
+        .get_window(&window_config.label)
+        .ok_or_else(|| Error::WindowNotFound(window_config.label.clone()))?;
+    manager.register_spotlight_window(&target, window_config)
+}
+
+/// Does nothing if the window isn't a registered spotlight window
+#[tauri::command]
+fn unregister_spotlight_window(manager: State<'_, spotlight::SpotlightManager>, window: Window<Wry>, label: String) -> Result<(), Error> {
+    match window.get_window(&label) {
+        Some(window) => manager.unregister_spotlight_window(&window),
+        None => manager.prune_window(&label),
+    }
+}
+
+/// Returns `false` if the accelerator is used by a spotlight window or registered by anything else
+/// in the app, fails if it is malformed
+#[tauri::command]
+fn is_shortcut_available(manager: State<'_, spotlight::SpotlightManager>, app_handle: AppHandle<Wry>, accelerator: String) -> Result<bool, Error> {
+    validate_accelerator(&accelerator)?;
+    // Close shortcuts of windows are only registered while they are focused, so the config is
+    // checked as well
+    if manager.config()?.shortcut_owner(&accelerator, "").is_some() {
+        return Ok(false);
+    }
+    let registered = shortcut_backend(&app_handle)
+        .is_registered(&accelerator)
+        .map_err(Error::query_failed(&accelerator))?;
+    Ok(!registered)
+}
+
+/// Generates the invoke handler of the commands, along with those of the dev tools if the feature
+/// is enabled
+#[cfg(feature = "dev-tools")]
+macro_rules! spotlight_handler {
+    ($($command:ident),* $(,)?) => {
+        tauri::generate_handler![$($command,)* reveal_all_spotlight]
+    };
+}
+
+#[cfg(not(feature = "dev-tools"))]
+macro_rules! spotlight_handler {
+    ($($command:ident),* $(,)?) => {
+        tauri::generate_handler![$($command),*]
+    };
 }
 
 pub fn init(spotlight_config: Option<PluginConfig>) -> TauriPlugin<Wry, Option<PluginConfig>> {
     Builder::<Wry, Option<PluginConfig>>::new("spotlight")
-        .invoke_handler(tauri::generate_handler![show, hide])
+        .invoke_handler(spotlight_handler![show, show_without_focus, show_on_monitor, move_to_next_monitor, set_next_position, set_show_blocked, hide, hide_all, show_all, toggle, get_spotlight_state, get_target_monitor, list_spotlight_windows, suspend_auto_hide, resume_auto_hide, reset_idle_timer, set_spotlight_auto_hide, set_spotlight_pinned, set_ignore_cursor_events, update_shortcut, set_global_close_shortcut, suspend_all_shortcuts, resume_all_shortcuts, set_spotlight_size, animate_height, recenter_spotlight, set_spotlight_opacity, register_spotlight_window, unregister_spotlight_window, is_shortcut_available, report_pending_input])
         .setup_with_config(|app, config| {
             app.manage(spotlight::SpotlightManager::new(
                 PluginConfig::merge(
-                    &spotlight_config.unwrap_or(PluginConfig::default()),
-                    &config.unwrap_or(PluginConfig::default()),
+                    &spotlight_config.unwrap_or_default(),
+                    &config.unwrap_or_default(),
                 )
             ));
             Ok(())
         })
         .on_webview_ready(move |window| {
             let app_handle = window.app_handle();
-            app_handle.spotlight().init_spotlight_window(&window).unwrap();
+            if let Err(err) = app_handle.spotlight().init_spotlight_window(&window) {
+                log_error!("failed to init spotlight window {}: {}", window.label(), err);
+            }
+        })
+        .on_event(|app_handle, event| {
+            if let RunEvent::WindowEvent { label, event: WindowEvent::Focused(focused), .. } = event {
+                // The manager only exists once the plugin was set up
+                let manager = match app_handle.try_state::<spotlight::SpotlightManager>() {
+                    Some(manager) => manager,
+                    None => return,
+                };
+                manager.app_focus().record(label, *focused);
+                if let Err(err) = app_focus::handle_focus_change(app_handle, *focused) {
+                    log_error!("failed to update local spotlight shortcuts: {}", err);
+                }
+            }
+        })
+        .on_drop(|app_handle| {
+            // The manager only exists once the plugin was set up
+            if let Some(manager) = app_handle.try_state::<spotlight::SpotlightManager>() {
+                if let Err(err) = manager.shutdown(&app_handle) {
+                    log_error!("failed to shut down spotlight windows: {}", err);
+                }
+            }
         })
         .build()
 }
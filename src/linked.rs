@@ -0,0 +1,75 @@
+use tauri::{Manager, Window, Wry};
+use crate::{animation, Error, ManagerExt};
+
+/// The registered spotlight windows shown and hidden along with the given one
+pub(crate) fn linked_windows(window: &Window<Wry>) -> Result<Vec<Window<Wry>>, Error> {
+    let manager = window.spotlight();
+    let mut windows = vec![];
+    for label in manager.config()?.linked_labels(window.label()) {
+        match window.get_window(&label) {
+            Some(linked) if manager.is_registered(&label) => windows.push(linked),
+            _ => log_warn!("spotlight window {} is linked to {}, which isn't registered", window.label(), label),
+        }
+    }
+    Ok(windows)
+}
+
+/// Hides the window after it lost focus, unless focus went to another window of its group, e.g.
+/// a linked window or one docked to it. A docked window takes its anchor along, which hides the
+/// rest of the group
+pub(crate) fn auto_hide(window: &Window<Wry>) -> Result<(), Error> {
+    unless_focus_stayed_in_group(window, |window| {
+        let manager = window.spotlight();
+        manager.hide(window)?;
+        let anchor = manager.get_window_config(window)?.and_then(|window_config| window_config.dock_to);
+        if let Some(anchor) = anchor.and_then(|dock_to| window.get_window(&dock_to.label)) {
+            if manager.is_registered(anchor.label()) {
+                manager.hide(&anchor)?;
+            }
+        }
+        Ok(())
+    })
+}
+
+/// Dims the window after it lost focus, unless focus went to another window of its group
+pub(crate) fn dim(window: &Window<Wry>, opacity: f64) -> Result<(), Error> {
+    unless_focus_stayed_in_group(window, move |window| animation::dim(window, opacity))
+}
+
+/// Which window got focus is only known once the focus change has been handled, so windows in a
+/// group are checked again from the event loop
+fn unless_focus_stayed_in_group<F>(window: &Window<Wry>, on_blur: F) -> Result<(), Error>
+where
+    F: FnOnce(&Window<Wry>) -> Result<(), Error> + Send + 'static,
+{
+    if window.spotlight().config()?.group_labels(window.label()).is_empty() {
+        return on_blur(window);
+    }
+    let w = window.clone();
+    // Running on the main thread right away would be too early, so the check is queued from another
+    std::thread::spawn(move || {
+        let window = w.clone();
+        let result = w.run_on_main_thread(move || {
+            let result = focus_stayed_in_group(&window).and_then(|stayed| if stayed { Ok(()) } else { on_blur(&window) });
+            if let Err(err) = result {
+                log_error!("failed to handle focus loss of spotlight window {}: {}", window.label(), err);
+            }
+        });
+        if let Err(err) = result {
+            log_error!("failed to handle focus loss of spotlight window {}: {}", w.label(), err);
+        }
+    });
+    Ok(())
+}
+
+fn focus_stayed_in_group(window: &Window<Wry>) -> Result<bool, Error> {
+    if window.is_focused()? {
+        return Ok(true);
+    }
+    let manager = window.spotlight();
+    let focused = match manager.app_focus().focused_window() {
+        Some(focused) => focused,
+        None => return Ok(false),
+    };
+    Ok(manager.is_registered(&focused) && manager.config()?.group_labels(window.label()).contains(&focused))
+}
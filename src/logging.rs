@@ -0,0 +1,29 @@
+/// Logs through the `log` crate at the given level if the `logging` feature is enabled, and
+/// compiles to nothing otherwise. The arguments are checked either way
+macro_rules! log_at {
+    ($level:ident, $($arg:tt)+) => {{
+        #[cfg(feature = "logging")]
+        log::$level!($($arg)+);
+        #[cfg(not(feature = "logging"))]
+        let _ = format_args!($($arg)+);
+    }};
+}
+
+/// Logs errors the plugin can't return, e.g. from shortcut handlers
+macro_rules! log_error {
+    ($($arg:tt)+) => { log_at!(error, $($arg)+) };
+}
+
+/// Logs failures the plugin carries on after, e.g. a window that is shown but not focused
+macro_rules! log_warn {
+    ($($arg:tt)+) => { log_at!(warn, $($arg)+) };
+}
+
+macro_rules! log_info {
+    ($($arg:tt)+) => { log_at!(info, $($arg)+) };
+}
+
+/// Logs what the plugin is doing, e.g. registering shortcuts and showing windows
+macro_rules! log_debug {
+    ($($arg:tt)+) => { log_at!(debug, $($arg)+) };
+}
@@ -0,0 +1,55 @@
+use tauri::{Window, Wry};
+use crate::Error;
+
+/// How long after showing the window characters typed outside of any input are buffered
+const PENDING_INPUT_WINDOW_MS: u32 = 1000;
+
+/// Buffers the characters typed into the page before an input has focus, e.g. while the page has
+/// yet to focus its search field, and reports them to the `report_pending_input` command once an
+/// input gets focus or the buffering window has passed. Showing the window again starts over
+pub(crate) fn capture_pending_input(window: &Window<Wry>) -> Result<(), Error> {
+    window.eval(&capture_script(PENDING_INPUT_WINDOW_MS))?;
+    Ok(())
+}
+
+fn capture_script(window_ms: u32) -> String {
+    format!(
+        "(function () {{ \
+            if (window.__SPOTLIGHT_PENDING_INPUT__) {{ window.__SPOTLIGHT_PENDING_INPUT__.flush(); }} \
+            let characters = ''; \
+            const editable = (element) => element && (element.isContentEditable || ['INPUT', 'TEXTAREA', 'SELECT'].includes(element.tagName)); \
+            const onKeyDown = (event) => {{ \
+                if (editable(event.target) || event.key.length !== 1 || event.ctrlKey || event.metaKey || event.altKey) {{ return; }} \
+                event.preventDefault(); \
+                characters += event.key; \
+            }}; \
+            const onFocusIn = (event) => {{ if (editable(event.target)) {{ flush(); }} }}; \
+            const timeout = setTimeout(() => flush(), {window_ms}); \
+            function flush() {{ \
+                clearTimeout(timeout); \
+                window.removeEventListener('keydown', onKeyDown, true); \
+                window.removeEventListener('focusin', onFocusIn, true); \
+                window.__SPOTLIGHT_PENDING_INPUT__ = undefined; \
+                if (characters) {{ window.__TAURI_INVOKE__('plugin:spotlight|report_pending_input', {{ characters }}); }} \
+            }} \
+            window.addEventListener('keydown', onKeyDown, true); \
+            window.addEventListener('focusin', onFocusIn, true); \
+            window.__SPOTLIGHT_PENDING_INPUT__ = {{ flush }}; \
+        }})();",
+        window_ms = window_ms,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::capture_script;
+
+    #[test]
+    fn report_buffered_characters_once() {
+        let script = capture_script(500);
+        assert!(script.contains("setTimeout(() => flush(), 500)"));
+        assert!(script.contains("plugin:spotlight|report_pending_input"));
+        assert!(script.starts_with("(function () {"));
+        assert!(script.ends_with("})();"));
+    }
+}
@@ -0,0 +1,126 @@
+use std::{collections::HashMap, fs, path::PathBuf};
+use tauri::{AppHandle, Manager, PhysicalPosition, PhysicalSize, Window, WindowEvent, Wry};
+use crate::{animation, Error, ManagerExt};
+use crate::position::center_in_range;
+
+const POSITIONS_FILE: &str = "spotlight-positions.json";
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
+struct SavedPosition {
+    x: i32,
+    y: i32,
+    /// Name of the monitor the window was on, if the platform reports one
+    monitor: Option<String>,
+}
+
+fn positions_file(app_handle: &AppHandle<Wry>) -> Result<PathBuf, Error> {
+    app_handle
+        .path_resolver()
+        .app_data_dir()
+        .map(|dir| dir.join(POSITIONS_FILE))
+        .ok_or_else(|| Error::Other(String::from("failed to resolve app data dir")))
+}
+
+/// Returns the saved positions by window label, a missing or corrupt file counts as empty
+fn read_positions(path: &PathBuf) -> HashMap<String, SavedPosition> {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+pub(crate) fn save_window_position(window: &Window<Wry>) -> Result<(), Error> {
+    let position = window.outer_position()?;
+    let monitor = window.current_monitor().map_err(Error::MonitorQueryFailed)?.and_then(|monitor| monitor.name().cloned());
+    let path = positions_file(&window.app_handle())?;
+    let mut positions = read_positions(&path);
+    positions.insert(window.label().to_string(), SavedPosition { x: position.x, y: position.y, monitor });
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let content = serde_json::to_string(&positions).map_err(|err| Error::Other(err.to_string()))?;
+    fs::write(&path, content)?;
+    Ok(())
+}
+
+/// Saves the position of the window to the app data dir every time it is moved, for as long as it
+/// is a spotlight window remembering its position. Moves made by the plugin's own animations aren't
+/// saved, they would write the file every frame and leave a window that slid off screen saved there
+pub(crate) fn remember_window_position(window: &Window<Wry>) {
+    let w = window.to_owned();
+    window.on_window_event(move |event| {
+        if let WindowEvent::Moved(_) = event {
+            let app_handle = w.app_handle();
+            let manager = app_handle.spotlight();
+            let remember = manager.is_registered(w.label())
+                && manager
+                    .get_window_config(&w)
+                    .ok()
+                    .flatten()
+                    .map_or(false, |window_config| window_config.should_remember_position());
+            if !remember || animation::is_animating(&w) || animation::is_resizing(&w) {
+                return;
+            }
+            if let Err(err) = save_window_position(&w) {
+                log_warn!("failed to save position of spotlight window {}: {}", w.label(), err);
+            }
+        }
+    });
+}
+
+/// Moves the window to its saved position, or centers it on the primary monitor if the monitor
+/// it was saved on is gone
+pub(crate) fn restore_window_position(window: &Window<Wry>) -> Result<(), Error> {
+    let saved = match read_positions(&positions_file(&window.app_handle())?).remove(window.label()) {
+        Some(saved) => saved,
+        None => return Ok(()),
+    };
+    let position = PhysicalPosition { x: saved.x, y: saved.y };
+    let on_screen = window.available_monitors().map_err(Error::MonitorQueryFailed)?.iter().any(|monitor| {
+        let same_monitor = saved.monitor.is_none() || monitor.name() == saved.monitor.as_ref();
+        same_monitor && contains(*monitor.position(), *monitor.size(), position)
+    });
+    if on_screen {
+        window.set_position(position)?;
+    } else if let Some(monitor) = window.primary_monitor().map_err(Error::MonitorQueryFailed)? {
+        let window_size = window.outer_size()?;
+        window.set_position(PhysicalPosition {
+            x: center_in_range(monitor.position().x as f64, monitor.size().width as f64, window_size.width as f64) as i32,
+            y: center_in_range(monitor.position().y as f64, monitor.size().height as f64, window_size.height as f64) as i32,
+        })?;
+    }
+    Ok(())
+}
+
+fn contains(origin: PhysicalPosition<i32>, size: PhysicalSize<u32>, point: PhysicalPosition<i32>) -> bool {
+    point.x >= origin.x
+        && point.y >= origin.y
+        && (point.x as i64) < origin.x as i64 + size.width as i64
+        && (point.y as i64) < origin.y as i64 + size.height as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use tauri::{PhysicalPosition, PhysicalSize};
+    use super::{contains, SavedPosition};
+
+    #[test]
+    fn point_inside_monitor() {
+        let origin = PhysicalPosition { x: -1920, y: 0 };
+        let size = PhysicalSize { width: 1920, height: 1080 };
+        assert!(contains(origin, size, PhysicalPosition { x: -1920, y: 0 }));
+        assert!(contains(origin, size, PhysicalPosition { x: -1, y: 1079 }));
+        assert!(!contains(origin, size, PhysicalPosition { x: 0, y: 0 }));
+        assert!(!contains(origin, size, PhysicalPosition { x: -100, y: 1080 }));
+    }
+
+    #[test]
+    fn round_trip_saved_positions() {
+        let mut positions = HashMap::new();
+        positions.insert(String::from("main"), SavedPosition { x: 10, y: -20, monitor: Some(String::from("DELL")) });
+        let content = serde_json::to_string(&positions).unwrap();
+        let parsed: HashMap<String, SavedPosition> = serde_json::from_str(&content).unwrap();
+        assert_eq!(parsed, positions);
+    }
+}
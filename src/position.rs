@@ -0,0 +1,213 @@
+use crate::config::{DockSide, PositionPreset};
+
+/// Returns the start of a span of `length` centered in the range `[start, start + available)`.
+/// Spans that don't fit are pinned to `start` so that they never go off-screen on that side.
+pub(crate) fn center_in_range(start: f64, available: f64, length: f64) -> f64 {
+    if length >= available {
+        start
+    } else {
+        start + (available - length) / 2.0
+    }
+}
+
+/// Returns the start of a span of `length` placed at `position`, moved into the range
+/// `[start, start + available)` if it would stick out. Spans that don't fit are pinned to `start`
+pub(crate) fn clamp_in_range(start: f64, available: f64, length: f64, position: f64) -> f64 {
+    position.min(start + available - length).max(start)
+}
+
+/// Returns the offset of the top edge of a window of `height` from the top of a work area of
+/// `available` height. Windows that don't fit are pinned to the top edge, and `TopCenter` never
+/// pushes the window past the bottom edge.
+pub(crate) fn offset_from_top(preset: PositionPreset, available: f64, height: f64) -> f64 {
+    if height >= available {
+        return 0.0;
+    }
+    match preset {
+        PositionPreset::TopCenter { y_offset_ratio } => (available * y_offset_ratio.clamp(0.0, 1.0)).min(available - height),
+        PositionPreset::Center => center_in_range(0.0, available, height),
+        PositionPreset::BottomCenter => available - height,
+    }
+}
+
+/// Returns the physical size of a window of `width` and `height` once the system rescales it from
+/// a monitor at `from_scale` to one at `to_scale`, e.g. 600 pixels wide at 100% are 900 at 150%
+pub(crate) fn rescale(width: f64, height: f64, from_scale: f64, to_scale: f64) -> (f64, f64) {
+    let scale = to_scale / from_scale;
+    (width * scale, height * scale)
+}
+
+/// A rectangle on the desktop, in physical pixels
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Rect {
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+impl Rect {
+    fn contains(&self, x: i32, y: i32) -> bool {
+        x >= self.x && x < self.x + self.width && y >= self.y && y < self.y + self.height
+    }
+
+    /// Squared distance from the point to the closest point of the rectangle
+    fn distance_squared(&self, x: i32, y: i32) -> i64 {
+        let dx = (self.x - x).max(x - (self.x + self.width - 1)).max(0) as i64;
+        let dy = (self.y - y).max(y - (self.y + self.height - 1)).max(0) as i64;
+        dx * dx + dy * dy
+    }
+}
+
+/// Returns where a window of the given size goes to sit on the side of the anchor, `gap` pixels
+/// away from it and lined up with its top or left edge
+pub(crate) fn dock_beside(anchor: Rect, width: i32, height: i32, side: DockSide, gap: i32) -> (i32, i32) {
+    match side {
+        DockSide::Right => (anchor.x + anchor.width + gap, anchor.y),
+        DockSide::Left => (anchor.x - gap - width, anchor.y),
+        DockSide::Below => (anchor.x, anchor.y + anchor.height + gap),
+        DockSide::Above => (anchor.x, anchor.y - gap - height),
+    }
+}
+
+/// Returns where `window` goes so that it stays on the monitors. It is kept inside the monitor
+/// containing its center, or the nearest one, except over edges bordering another monitor so that
+/// it can still be dragged across. Windows larger than the monitor are pinned to its top-left corner
+pub(crate) fn constrain_to_monitors(window: Rect, monitors: &[Rect]) -> (i32, i32) {
+    let (center_x, center_y) = (window.x + window.width / 2, window.y + window.height / 2);
+    let home = match monitors
+        .iter()
+        .find(|monitor| monitor.contains(center_x, center_y))
+        .or_else(|| monitors.iter().min_by_key(|monitor| monitor.distance_squared(center_x, center_y)))
+    {
+        Some(home) => home,
+        None => return (window.x, window.y),
+    };
+    let bordered = |x: i32, y: i32| monitors.iter().any(|monitor| monitor != home && monitor.contains(x, y));
+    let (mut x, mut y) = (window.x, window.y);
+    if x + window.width > home.x + home.width && !bordered(x + window.width - 1, center_y) {
+        x = home.x + home.width - window.width;
+    }
+    if x < home.x && !bordered(window.x, center_y) {
+        x = home.x;
+    }
+    if y + window.height > home.y + home.height && !bordered(center_x, y + window.height - 1) {
+        y = home.y + home.height - window.height;
+    }
+    if y < home.y && !bordered(center_x, window.y) {
+        y = home.y;
+    }
+    (x, y)
+}
+
+/// Index of the monitor after the current one, the last one wrapping around to the first. A window
+/// that isn't on any of the monitors goes to the first one
+pub(crate) fn next_monitor_index(current: Option<usize>, count: usize) -> Option<usize> {
+    if count == 0 {
+        return None;
+    }
+    Some(current.map_or(0, |current| (current + 1) % count))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{center_in_range, clamp_in_range, constrain_to_monitors, dock_beside, next_monitor_index, offset_from_top, rescale, Rect};
+    use crate::config::{DockSide, PositionPreset};
+
+    #[test]
+    fn clamp_span_into_range() {
+        assert_eq!(clamp_in_range(0.0, 1000.0, 200.0, 300.0), 300.0);
+        assert_eq!(clamp_in_range(0.0, 1000.0, 200.0, 900.0), 800.0);
+        assert_eq!(clamp_in_range(100.0, 1000.0, 200.0, 50.0), 100.0);
+        assert_eq!(clamp_in_range(0.0, 1000.0, 1200.0, 300.0), 0.0);
+    }
+
+    #[test]
+    fn centers_span_inside_range() {
+        assert_eq!(center_in_range(0.0, 1920.0, 600.0), 660.0);
+        assert_eq!(center_in_range(-1280.0, 1280.0, 400.0), -840.0);
+    }
+
+    #[test]
+    fn clamps_span_larger_than_range() {
+        assert_eq!(center_in_range(100.0, 800.0, 1000.0), 100.0);
+        assert_eq!(center_in_range(100.0, 800.0, 800.0), 100.0);
+    }
+
+    #[test]
+    fn offset_window_by_preset() {
+        let top = PositionPreset::TopCenter { y_offset_ratio: 0.25 };
+        assert_eq!(offset_from_top(top, 1000.0, 400.0), 250.0);
+        assert_eq!(offset_from_top(PositionPreset::Center, 1000.0, 400.0), 300.0);
+        assert_eq!(offset_from_top(PositionPreset::BottomCenter, 1000.0, 400.0), 600.0);
+    }
+
+    #[test]
+    fn keep_offset_window_inside_work_area() {
+        let top = PositionPreset::TopCenter { y_offset_ratio: 0.9 };
+        assert_eq!(offset_from_top(top, 1000.0, 400.0), 600.0);
+        let top = PositionPreset::TopCenter { y_offset_ratio: -1.0 };
+        assert_eq!(offset_from_top(top, 1000.0, 400.0), 0.0);
+        assert_eq!(offset_from_top(PositionPreset::BottomCenter, 1000.0, 1200.0), 0.0);
+    }
+
+    const LEFT: Rect = Rect { x: 0, y: 0, width: 1920, height: 1080 };
+    const RIGHT: Rect = Rect { x: 1920, y: 0, width: 1280, height: 720 };
+
+    #[test]
+    fn keep_window_inside_its_monitor() {
+        let window = Rect { x: -300, y: 900, width: 600, height: 400 };
+        assert_eq!(constrain_to_monitors(window, &[LEFT]), (0, 680));
+        let window = Rect { x: 100, y: 100, width: 600, height: 400 };
+        assert_eq!(constrain_to_monitors(window, &[LEFT]), (100, 100));
+    }
+
+    #[test]
+    fn drag_window_across_bordering_monitor() {
+        let window = Rect { x: 1600, y: 100, width: 600, height: 400 };
+        assert_eq!(constrain_to_monitors(window, &[LEFT, RIGHT]), (1600, 100));
+        // Past the bottom of the smaller monitor nothing borders it
+        let window = Rect { x: 2000, y: 500, width: 600, height: 400 };
+        assert_eq!(constrain_to_monitors(window, &[LEFT, RIGHT]), (2000, 320));
+    }
+
+    #[test]
+    fn bring_window_back_from_off_all_monitors() {
+        let window = Rect { x: 4000, y: 2000, width: 600, height: 400 };
+        assert_eq!(constrain_to_monitors(window, &[LEFT, RIGHT]), (2600, 320));
+        let window = Rect { x: 0, y: 0, width: 2000, height: 400 };
+        assert_eq!(constrain_to_monitors(window, &[Rect { x: 0, y: 0, width: 1280, height: 720 }]), (0, 0));
+        assert_eq!(constrain_to_monitors(window, &[]), (0, 0));
+    }
+
+    #[test]
+    fn dock_on_each_side_of_anchor() {
+        let anchor = Rect { x: 100, y: 200, width: 600, height: 80 };
+        assert_eq!(dock_beside(anchor, 400, 300, DockSide::Right, 8), (708, 200));
+        assert_eq!(dock_beside(anchor, 400, 300, DockSide::Left, 8), (-308, 200));
+        assert_eq!(dock_beside(anchor, 400, 300, DockSide::Below, 8), (100, 288));
+        assert_eq!(dock_beside(anchor, 400, 300, DockSide::Above, 0), (100, -100));
+    }
+
+    #[test]
+    fn cycle_through_monitors() {
+        assert_eq!(next_monitor_index(Some(0), 3), Some(1));
+        assert_eq!(next_monitor_index(Some(2), 3), Some(0));
+        assert_eq!(next_monitor_index(Some(0), 1), Some(0));
+        assert_eq!(next_monitor_index(None, 2), Some(0));
+        assert_eq!(next_monitor_index(None, 0), None);
+    }
+
+    #[test]
+    fn center_on_scaled_monitor_left_of_primary() {
+        // A 1920x1080 monitor at 150% left of the primary 1920x1080 one at 100%, the window is
+        // 600x400 on the primary monitor
+        let (width, height) = rescale(600.0, 400.0, 1.0, 1.5);
+        assert_eq!((width, height), (900.0, 600.0));
+        assert_eq!(center_in_range(-2880.0, 2880.0, width), -1890.0);
+        assert_eq!(offset_from_top(PositionPreset::Center, 1620.0, height), 510.0);
+        // And back onto the primary monitor
+        let (width, _) = rescale(width, height, 1.5, 1.0);
+        assert_eq!(center_in_range(0.0, 1920.0, width), 660.0);
+    }
+}
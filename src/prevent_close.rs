@@ -0,0 +1,26 @@
+use tauri::{Window, WindowEvent, Wry};
+use crate::ManagerExt;
+
+/// Hides the window instead of letting it close, e.g. from the close button of its title bar, for
+/// as long as it is a spotlight window that prevents closing. Its shortcuts keep working then
+pub(crate) fn hide_instead_of_closing(window: &Window<Wry>) {
+    let w = window.to_owned();
+    window.on_window_event(move |event| {
+        if let WindowEvent::CloseRequested { api, .. } = event {
+            let manager = w.spotlight();
+            let prevent = manager.is_registered(w.label())
+                && manager
+                    .get_window_config(&w)
+                    .ok()
+                    .flatten()
+                    .map_or(false, |window_config| window_config.should_prevent_close());
+            if !prevent {
+                return;
+            }
+            api.prevent_close();
+            if let Err(err) = manager.hide(&w) {
+                log_error!("failed to hide spotlight window {} instead of closing it: {}", w.label(), err);
+            }
+        }
+    });
+}
@@ -0,0 +1,54 @@
+use std::{collections::HashMap, sync::Mutex};
+use crate::Error;
+
+/// Remembers what had focus before each spotlight window was shown, as a platform specific handle:
+/// the window handle on Windows and the process id of the app on macOS
+#[derive(Default, Debug)]
+pub(crate) struct PreviousFocus {
+    handles: Mutex<HashMap<String, isize>>,
+}
+
+impl PreviousFocus {
+    pub(crate) fn remember(&self, label: &str, handle: Option<isize>) -> Result<(), Error> {
+        let mut handles = self
+            .handles
+            .lock()
+            .map_err(|_| Error::Mutex(String::from("failed to lock previous focus")))?;
+        match handle {
+            Some(handle) => handles.insert(label.to_string(), handle),
+            None => handles.remove(label),
+        };
+        Ok(())
+    }
+
+    /// Returns what had focus before the window was shown, it is only handed out once
+    pub(crate) fn take(&self, label: &str) -> Result<Option<isize>, Error> {
+        let mut handles = self
+            .handles
+            .lock()
+            .map_err(|_| Error::Mutex(String::from("failed to lock previous focus")))?;
+        Ok(handles.remove(label))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PreviousFocus;
+
+    #[test]
+    fn hand_out_previous_focus_once() {
+        let previous_focus = PreviousFocus::default();
+        previous_focus.remember("main", Some(42)).unwrap();
+        assert_eq!(previous_focus.take("foo").unwrap(), None);
+        assert_eq!(previous_focus.take("main").unwrap(), Some(42));
+        assert_eq!(previous_focus.take("main").unwrap(), None);
+    }
+
+    #[test]
+    fn forget_previous_focus_when_there_is_none() {
+        let previous_focus = PreviousFocus::default();
+        previous_focus.remember("main", Some(42)).unwrap();
+        previous_focus.remember("main", None).unwrap();
+        assert_eq!(previous_focus.take("main").unwrap(), None);
+    }
+}
@@ -0,0 +1,150 @@
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use crate::Error;
+
+/// Labels of the registered spotlight windows, in the order they were registered
+#[derive(Default, Debug)]
+pub(crate) struct RegisteredWindows {
+    labels: Mutex<Vec<String>>,
+}
+
+impl RegisteredWindows {
+    /// Every change to the labels is made in one step, so a panic while they were locked can't
+    /// leave them half updated. The lock is recovered then, instead of failing every shortcut press
+    /// from then on
+    fn lock(&self) -> MutexGuard<'_, Vec<String>> {
+        self.labels.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    pub(crate) fn contains(&self, label: &str) -> bool {
+        self.lock().iter().any(|registered| registered == label)
+    }
+
+    /// Registers the window, returns `false` if it already is
+    pub(crate) fn insert(&self, label: &str) -> bool {
+        let mut labels = self.lock();
+        if labels.iter().any(|registered| registered == label) {
+            return false;
+        }
+        labels.push(label.to_string());
+        true
+    }
+
+    /// Registers the window and sets it up with `set_up`. A window that is registered already, e.g.
+    /// as it was recreated with the same label, gets `register_again` instead. The window is claimed
+    /// before it is set up, so that it is never set up twice, and given up again if that fails
+    pub(crate) fn init<S, A>(&self, label: &str, set_up: S, register_again: A) -> Result<(), Error>
+    where
+        S: FnOnce() -> Result<(), Error>,
+        A: FnOnce() -> Result<(), Error>,
+    {
+        if !self.insert(label) {
+            return register_again();
+        }
+        let result = set_up();
+        if result.is_err() {
+            self.remove(label);
+        }
+        result
+    }
+
+    pub(crate) fn remove(&self, label: &str) {
+        self.lock().retain(|registered| registered != label);
+    }
+
+    pub(crate) fn labels(&self) -> Vec<String> {
+        self.lock().clone()
+    }
+
+    /// Forgets the windows that don't exist anymore and returns their labels
+    pub(crate) fn prune<F: Fn(&str) -> bool>(&self, exists: F) -> Vec<String> {
+        let mut labels = self.lock();
+        // Asked before changing anything, in case `exists` panics
+        let dead: Vec<String> = labels.iter().filter(|label| !exists(label)).cloned().collect();
+        labels.retain(|label| !dead.contains(label));
+        dead
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use super::RegisteredWindows;
+    use crate::shortcut_backend::{mock::MockShortcuts, register_exclusive, register_replacing};
+
+    #[test]
+    fn reject_double_registration() {
+        let registered = RegisteredWindows::default();
+        assert!(registered.insert("main"));
+        assert!(!registered.insert("main"));
+        assert_eq!(registered.labels(), vec!["main"]);
+    }
+
+    #[test]
+    fn register_again_after_removal() {
+        let registered = RegisteredWindows::default();
+        registered.insert("main");
+        registered.remove("main");
+        assert!(!registered.contains("main"));
+        assert!(registered.insert("main"));
+    }
+
+    #[test]
+    fn prune_dead_labels() {
+        let registered = RegisteredWindows::default();
+        for label in ["main", "closed", "secondary"] {
+            registered.insert(label);
+        }
+        assert_eq!(registered.prune(|label| label != "closed"), vec!["closed"]);
+        assert_eq!(registered.labels(), vec!["main", "secondary"]);
+        assert!(registered.prune(|_| true).is_empty());
+    }
+
+    #[test]
+    fn recover_from_poisoned_lock() {
+        let registered = std::sync::Arc::new(RegisteredWindows::default());
+        registered.insert("main");
+        let poisoner = registered.clone();
+        let result = std::thread::spawn(move || {
+            let _labels = poisoner.labels.lock().unwrap();
+            panic!("panicked while holding the lock");
+        })
+        .join();
+        assert!(result.is_err());
+        assert!(registered.contains("main"));
+        assert!(registered.insert("secondary"));
+    }
+
+    #[test]
+    fn init_same_label_twice() {
+        let registered = RegisteredWindows::default();
+        let shortcuts = RefCell::new(MockShortcuts::default());
+        // First when the window is created, then again after it was recreated with the same label
+        for _ in 0..2 {
+            registered
+                .init(
+                    "main",
+                    || register_exclusive(&mut *shortcuts.borrow_mut(), "main", "Ctrl+Shift+J", Box::new(|| {})),
+                    || register_replacing(&mut *shortcuts.borrow_mut(), "main", "Ctrl+Shift+J", Box::new(|| {})),
+                )
+                .unwrap();
+        }
+        let shortcuts = shortcuts.into_inner();
+        assert_eq!(registered.labels(), vec!["main"]);
+        assert_eq!(shortcuts.registered.len(), 1);
+        assert_eq!((shortcuts.registrations, shortcuts.unregistrations), (2, 1));
+    }
+
+    #[test]
+    fn give_up_window_whose_set_up_failed() {
+        let registered = RegisteredWindows::default();
+        let mut shortcuts = MockShortcuts { failing_queries: true, ..Default::default() };
+        let result = registered.init(
+            "main",
+            || register_exclusive(&mut shortcuts, "main", "Ctrl+Shift+J", Box::new(|| {})),
+            || unreachable!("the window isn't registered yet"),
+        );
+        assert!(result.is_err());
+        assert!(!registered.contains("main"));
+        assert!(shortcuts.registered.is_empty());
+    }
+}
@@ -0,0 +1,14 @@
+use tauri::{Window, Wry};
+use crate::Error;
+
+/// Takes the window out of the minimized and fullscreen states, so it is placed and shown as the
+/// regular window it was configured as
+pub(crate) fn restore_normal_state(window: &Window<Wry>) -> Result<(), Error> {
+    if window.is_minimized()? {
+        window.unminimize()?;
+    }
+    if window.is_fullscreen()? {
+        window.set_fullscreen(false)?;
+    }
+    Ok(())
+}
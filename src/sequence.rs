@@ -0,0 +1,144 @@
+use std::{collections::HashMap, sync::Mutex, time::{Duration, Instant}};
+use crate::Error;
+
+/// What pressing a step of a shortcut sequence led to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SequenceStep {
+    /// The first step was pressed, the rest of the sequence is awaited
+    Started,
+    /// The next step was pressed in time
+    Advanced,
+    /// The last step was pressed in time
+    Completed,
+    /// A step was pressed out of order, the sequence starts over
+    Reset,
+}
+
+struct Progress {
+    /// Index of the step awaited next
+    next: usize,
+    deadline: Instant,
+}
+
+/// Keeps track of how far into its shortcut sequence each window is
+#[derive(Default)]
+pub(crate) struct ShortcutSequences {
+    progress: Mutex<HashMap<String, Progress>>,
+}
+
+impl std::fmt::Debug for ShortcutSequences {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ShortcutSequences").finish_non_exhaustive()
+    }
+}
+
+impl ShortcutSequences {
+    /// Records that `accelerator` was pressed for the window's `sequence`, every step has to follow
+    /// the previous one within `timeout`
+    pub(crate) fn press(&self, label: &str, sequence: &[String], accelerator: &str, timeout: Duration, now: Instant) -> Result<SequenceStep, Error> {
+        let mut progress = self
+            .progress
+            .lock()
+            .map_err(|_| Error::Mutex(String::from("failed to lock shortcut sequences")))?;
+        let next = progress
+            .get(label)
+            .filter(|progress| now < progress.deadline)
+            .map_or(0, |progress| progress.next);
+        if next > 0 && sequence.get(next).map(String::as_str) == Some(accelerator) {
+            if next + 1 == sequence.len() {
+                progress.remove(label);
+                return Ok(SequenceStep::Completed);
+            }
+            progress.insert(label.to_string(), Progress { next: next + 1, deadline: now + timeout });
+            return Ok(SequenceStep::Advanced);
+        }
+        // Pressing the first step again, even halfway through, starts the sequence over
+        if sequence.first().map(String::as_str) == Some(accelerator) {
+            if sequence.len() == 1 {
+                progress.remove(label);
+                return Ok(SequenceStep::Completed);
+            }
+            progress.insert(label.to_string(), Progress { next: 1, deadline: now + timeout });
+            return Ok(SequenceStep::Started);
+        }
+        progress.remove(label);
+        Ok(SequenceStep::Reset)
+    }
+
+    /// Gives up on the window's sequence if the next step wasn't pressed in time, returns `true` if
+    /// it did give up
+    pub(crate) fn expire(&self, label: &str, now: Instant) -> Result<bool, Error> {
+        let mut progress = self
+            .progress
+            .lock()
+            .map_err(|_| Error::Mutex(String::from("failed to lock shortcut sequences")))?;
+        match progress.get(label) {
+            Some(p) if now >= p.deadline => {
+                progress.remove(label);
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    pub(crate) fn forget(&self, label: &str) -> Result<(), Error> {
+        let mut progress = self
+            .progress
+            .lock()
+            .map_err(|_| Error::Mutex(String::from("failed to lock shortcut sequences")))?;
+        progress.remove(label);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+    use super::{SequenceStep, ShortcutSequences};
+
+    fn sequence() -> Vec<String> {
+        vec![String::from("Ctrl+Space"), String::from("K"), String::from("J")]
+    }
+
+    #[test]
+    fn complete_sequence_in_time() {
+        let sequences = ShortcutSequences::default();
+        let timeout = Duration::from_millis(500);
+        let start = Instant::now();
+        let press = |accelerator, ms| sequences.press("main", &sequence(), accelerator, timeout, start + Duration::from_millis(ms)).unwrap();
+        assert_eq!(press("Ctrl+Space", 0), SequenceStep::Started);
+        assert_eq!(press("K", 300), SequenceStep::Advanced);
+        // The timeout starts over with every step
+        assert_eq!(press("J", 700), SequenceStep::Completed);
+        assert_eq!(press("K", 800), SequenceStep::Reset);
+    }
+
+    #[test]
+    fn reset_on_step_out_of_order() {
+        let sequences = ShortcutSequences::default();
+        let timeout = Duration::from_millis(500);
+        let now = Instant::now();
+        assert_eq!(sequences.press("main", &sequence(), "Ctrl+Space", timeout, now).unwrap(), SequenceStep::Started);
+        assert_eq!(sequences.press("main", &sequence(), "J", timeout, now).unwrap(), SequenceStep::Reset);
+        assert_eq!(sequences.press("main", &sequence(), "K", timeout, now).unwrap(), SequenceStep::Reset);
+        // The first step starts over even halfway through
+        sequences.press("main", &sequence(), "Ctrl+Space", timeout, now).unwrap();
+        assert_eq!(sequences.press("main", &sequence(), "Ctrl+Space", timeout, now).unwrap(), SequenceStep::Started);
+    }
+
+    #[test]
+    fn expire_after_timeout() {
+        let sequences = ShortcutSequences::default();
+        let timeout = Duration::from_millis(500);
+        let start = Instant::now();
+        sequences.press("main", &sequence(), "Ctrl+Space", timeout, start).unwrap();
+        assert!(!sequences.expire("main", start + Duration::from_millis(400)).unwrap());
+        assert_eq!(
+            sequences.press("main", &sequence(), "K", timeout, start + Duration::from_millis(600)).unwrap(),
+            SequenceStep::Reset,
+        );
+        sequences.press("main", &sequence(), "Ctrl+Space", timeout, start).unwrap();
+        assert!(sequences.expire("main", start + Duration::from_millis(500)).unwrap());
+        assert!(!sequences.expire("main", start + Duration::from_millis(500)).unwrap());
+    }
+}
@@ -0,0 +1,120 @@
+use tauri::{AppHandle, GlobalShortcutManager, Wry};
+use crate::Error;
+use crate::accelerator::validate_accelerator;
+
+pub(crate) type ShortcutHandler = Box<dyn Fn() + Send + 'static>;
+
+/// The part of the global shortcut manager the plugin relies on, so that the bookkeeping around it
+/// can be tested without registering real shortcuts
+pub(crate) trait ShortcutBackend {
+    fn is_registered(&self, accelerator: &str) -> Result<bool, tauri::Error>;
+    fn register(&mut self, accelerator: &str, handler: ShortcutHandler) -> Result<(), tauri::Error>;
+    fn unregister(&mut self, accelerator: &str) -> Result<(), tauri::Error>;
+}
+
+impl<T: GlobalShortcutManager> ShortcutBackend for T {
+    fn is_registered(&self, accelerator: &str) -> Result<bool, tauri::Error> {
+        GlobalShortcutManager::is_registered(self, accelerator).map_err(tauri::Error::from)
+    }
+
+    fn register(&mut self, accelerator: &str, handler: ShortcutHandler) -> Result<(), tauri::Error> {
+        GlobalShortcutManager::register(self, accelerator, handler).map_err(tauri::Error::from)
+    }
+
+    fn unregister(&mut self, accelerator: &str) -> Result<(), tauri::Error> {
+        GlobalShortcutManager::unregister(self, accelerator).map_err(tauri::Error::from)
+    }
+}
+
+/// The backend every shortcut of the plugin is registered with
+pub(crate) fn shortcut_backend(app_handle: &AppHandle<Wry>) -> impl ShortcutBackend {
+    app_handle.global_shortcut_manager()
+}
+
+/// Registers a shortcut only the given window may have, failing if it is registered already
+pub(crate) fn register_exclusive<B: ShortcutBackend>(backend: &mut B, label: &str, accelerator: &str, handler: ShortcutHandler) -> Result<(), Error> {
+    validate_accelerator(accelerator)?;
+    let registered = backend.is_registered(accelerator).map_err(Error::query_failed(accelerator))?;
+    if registered {
+        return Err(Error::ShortcutConflict(format!("{} of window {} is already registered", accelerator, label)));
+    }
+    backend.register(accelerator, handler).map_err(Error::register_failed(accelerator))?;
+    log_debug!("registered shortcut {} of spotlight window {}", accelerator, label);
+    Ok(())
+}
+
+/// Registers a shortcut of a window that is registered again, e.g. after it was recreated, so that
+/// it calls the new handler. The registration of the window's previous handler is replaced rather
+/// than registered twice
+pub(crate) fn register_replacing<B: ShortcutBackend>(backend: &mut B, label: &str, accelerator: &str, handler: ShortcutHandler) -> Result<(), Error> {
+    if backend.is_registered(accelerator).map_err(Error::query_failed(accelerator))? {
+        backend.unregister(accelerator).map_err(Error::unregister_failed(accelerator))?;
+    }
+    register_exclusive(backend, label, accelerator, handler)
+}
+
+#[cfg(test)]
+pub(crate) mod mock {
+    use std::collections::HashSet;
+    use super::{ShortcutBackend, ShortcutHandler};
+
+    /// Remembers which shortcuts are registered instead of registering them with the OS
+    #[derive(Default)]
+    pub(crate) struct MockShortcuts {
+        pub(crate) registered: HashSet<String>,
+        /// How many times shortcuts were registered or unregistered
+        pub(crate) registrations: usize,
+        pub(crate) unregistrations: usize,
+        /// Makes every query fail, like the shortcut manager does for accelerators it can't parse
+        pub(crate) failing_queries: bool,
+    }
+
+    impl ShortcutBackend for MockShortcuts {
+        fn is_registered(&self, accelerator: &str) -> Result<bool, tauri::Error> {
+            if self.failing_queries {
+                return Err(tauri::Error::FailedToSendMessage);
+            }
+            Ok(self.registered.contains(accelerator))
+        }
+
+        fn register(&mut self, accelerator: &str, _handler: ShortcutHandler) -> Result<(), tauri::Error> {
+            self.registered.insert(accelerator.to_string());
+            self.registrations += 1;
+            Ok(())
+        }
+
+        fn unregister(&mut self, accelerator: &str) -> Result<(), tauri::Error> {
+            self.registered.remove(accelerator);
+            self.unregistrations += 1;
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::mock::MockShortcuts;
+    use super::{register_exclusive, register_replacing};
+    use crate::Error;
+
+    #[test]
+    fn reject_double_registration() {
+        let mut shortcuts = MockShortcuts::default();
+        register_exclusive(&mut shortcuts, "main", "Ctrl+Shift+J", Box::new(|| {})).unwrap();
+        let result = register_exclusive(&mut shortcuts, "main", "Ctrl+Shift+J", Box::new(|| {}));
+        assert!(matches!(result, Err(Error::ShortcutConflict(_))));
+        assert_eq!(shortcuts.registrations, 1);
+    }
+
+    #[test]
+    fn tell_failed_queries_from_invalid_shortcuts() {
+        let mut shortcuts = MockShortcuts { failing_queries: true, ..Default::default() };
+        let result = register_exclusive(&mut shortcuts, "main", "Ctrl+Shift+J", Box::new(|| {}));
+        assert!(matches!(result, Err(Error::ShortcutQueryFailed { .. })));
+        let result = register_exclusive(&mut shortcuts, "main", "Ctrl+Foo", Box::new(|| {}));
+        assert!(matches!(result, Err(Error::InvalidShortcut(_))));
+        let result = register_replacing(&mut shortcuts, "main", "Ctrl+Shift+J", Box::new(|| {}));
+        assert!(matches!(result, Err(Error::ShortcutQueryFailed { .. })));
+        assert_eq!(shortcuts.registrations, 0);
+    }
+}
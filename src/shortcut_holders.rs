@@ -0,0 +1,162 @@
+use std::{collections::HashSet, sync::Mutex};
+use crate::Error;
+use crate::shortcut_backend::{ShortcutBackend, ShortcutHandler};
+
+/// Keeps track of the windows that need a shared shortcut, so that it stays registered until the
+/// last of them lets go of it
+#[derive(Default, Debug)]
+pub(crate) struct ShortcutHolders {
+    holders: Mutex<HashSet<String>>,
+}
+
+impl ShortcutHolders {
+    /// Records that the window needs the shortcut, holding it more than once counts once
+    pub(crate) fn acquire(&self, label: &str) -> Result<(), Error> {
+        let mut holders = self
+            .holders
+            .lock()
+            .map_err(|_| Error::Mutex(String::from("failed to lock shortcut holders")))?;
+        holders.insert(label.to_string());
+        Ok(())
+    }
+
+    /// Drops the window from the holders and returns `true` if no window needs the shortcut anymore
+    pub(crate) fn release(&self, label: &str) -> Result<bool, Error> {
+        let mut holders = self
+            .holders
+            .lock()
+            .map_err(|_| Error::Mutex(String::from("failed to lock shortcut holders")))?;
+        holders.remove(label);
+        Ok(holders.is_empty())
+    }
+
+    /// Holds the shortcut for the window, registering it unless another window already holds it
+    pub(crate) fn acquire_shortcut<B: ShortcutBackend>(&self, backend: &mut B, label: &str, accelerator: &str, handler: ShortcutHandler) -> Result<(), Error> {
+        self.acquire(label)?;
+        let result = match backend.is_registered(accelerator) {
+            Ok(true) => Ok(()),
+            Ok(false) => backend.register(accelerator, handler).map_err(Error::register_failed(accelerator)),
+            Err(err) => Err(Error::query_failed(accelerator)(err)),
+        };
+        if result.is_err() {
+            self.release(label)?;
+        } else {
+            log_debug!("spotlight window {} holds shared shortcut {}", label, accelerator);
+        }
+        result
+    }
+
+    /// Moves the shortcut to another accelerator for the windows holding it. The new one is
+    /// registered before the old one is unregistered, so a failure leaves the old one in place.
+    /// Nothing is registered while no window holds the shortcut
+    pub(crate) fn replace_shortcut<B: ShortcutBackend>(&self, backend: &mut B, old: &str, new: &str, handler: ShortcutHandler) -> Result<(), Error> {
+        let held = !self
+            .holders
+            .lock()
+            .map_err(|_| Error::Mutex(String::from("failed to lock shortcut holders")))?
+            .is_empty();
+        if !held {
+            return Ok(());
+        }
+        if !backend.is_registered(new).map_err(Error::query_failed(new))? {
+            backend.register(new, handler).map_err(Error::register_failed(new))?;
+        }
+        if backend.is_registered(old).map_err(Error::query_failed(old))? {
+            backend.unregister(old).map_err(Error::unregister_failed(old))?;
+        }
+        log_debug!("replaced shared shortcut {} with {}", old, new);
+        Ok(())
+    }
+
+    /// Lets go of the shortcut for the window, unregistering it if no other window holds it
+    pub(crate) fn release_shortcut<B: ShortcutBackend>(&self, backend: &mut B, label: &str, accelerator: &str) -> Result<(), Error> {
+        if !self.release(label)? {
+            return Ok(());
+        }
+        if backend.is_registered(accelerator).map_err(Error::query_failed(accelerator))? {
+            backend.unregister(accelerator).map_err(Error::unregister_failed(accelerator))?;
+            log_debug!("unregistered shared shortcut {}, spotlight window {} was the last to hold it", accelerator, label);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ShortcutHolders;
+    use crate::shortcut_backend::mock::MockShortcuts;
+    use crate::Error;
+
+    #[test]
+    fn keep_shortcut_until_last_holder_releases_it() {
+        let holders = ShortcutHolders::default();
+        holders.acquire("main").unwrap();
+        holders.acquire("foo").unwrap();
+        assert!(!holders.release("main").unwrap());
+        assert!(holders.release("foo").unwrap());
+    }
+
+    #[test]
+    fn count_each_holder_once() {
+        let holders = ShortcutHolders::default();
+        holders.acquire("main").unwrap();
+        holders.acquire("main").unwrap();
+        assert!(holders.release("main").unwrap());
+        // Releasing a window that doesn't hold the shortcut changes nothing
+        holders.acquire("foo").unwrap();
+        assert!(!holders.release("main").unwrap());
+    }
+
+    #[test]
+    fn register_shared_shortcut_once() {
+        let holders = ShortcutHolders::default();
+        let mut shortcuts = MockShortcuts::default();
+        holders.acquire_shortcut(&mut shortcuts, "main", "Escape", Box::new(|| {})).unwrap();
+        holders.acquire_shortcut(&mut shortcuts, "foo", "Escape", Box::new(|| {})).unwrap();
+        assert_eq!(shortcuts.registrations, 1);
+        holders.release_shortcut(&mut shortcuts, "main", "Escape").unwrap();
+        assert!(shortcuts.registered.contains("Escape"));
+        holders.release_shortcut(&mut shortcuts, "foo", "Escape").unwrap();
+        assert!(!shortcuts.registered.contains("Escape"));
+        assert_eq!(shortcuts.unregistrations, 1);
+    }
+
+    #[test]
+    fn keep_shared_shortcut_when_holder_releases_twice() {
+        let holders = ShortcutHolders::default();
+        let mut shortcuts = MockShortcuts::default();
+        holders.acquire_shortcut(&mut shortcuts, "main", "Escape", Box::new(|| {})).unwrap();
+        holders.acquire_shortcut(&mut shortcuts, "foo", "Escape", Box::new(|| {})).unwrap();
+        holders.release_shortcut(&mut shortcuts, "main", "Escape").unwrap();
+        holders.release_shortcut(&mut shortcuts, "main", "Escape").unwrap();
+        assert!(shortcuts.registered.contains("Escape"));
+    }
+
+    #[test]
+    fn replace_held_shortcut() {
+        let holders = ShortcutHolders::default();
+        let mut shortcuts = MockShortcuts::default();
+        holders.replace_shortcut(&mut shortcuts, "Escape", "Ctrl+W", Box::new(|| {})).unwrap();
+        assert_eq!(shortcuts.registrations, 0);
+        holders.acquire_shortcut(&mut shortcuts, "main", "Escape", Box::new(|| {})).unwrap();
+        holders.replace_shortcut(&mut shortcuts, "Escape", "Ctrl+W", Box::new(|| {})).unwrap();
+        assert!(!shortcuts.registered.contains("Escape"));
+        assert!(shortcuts.registered.contains("Ctrl+W"));
+        holders.release_shortcut(&mut shortcuts, "main", "Ctrl+W").unwrap();
+        assert!(shortcuts.registered.is_empty());
+    }
+
+    #[test]
+    fn release_holder_when_query_fails() {
+        let holders = ShortcutHolders::default();
+        let mut shortcuts = MockShortcuts { failing_queries: true, ..Default::default() };
+        let result = holders.acquire_shortcut(&mut shortcuts, "main", "Escape", Box::new(|| {}));
+        assert!(matches!(result, Err(Error::ShortcutQueryFailed { accelerator, .. }) if accelerator == "Escape"));
+        assert_eq!(shortcuts.registrations, 0);
+        // The failed window doesn't hold the shortcut, so it is released along with the next one
+        shortcuts.failing_queries = false;
+        holders.acquire_shortcut(&mut shortcuts, "foo", "Escape", Box::new(|| {})).unwrap();
+        holders.release_shortcut(&mut shortcuts, "foo", "Escape").unwrap();
+        assert!(!shortcuts.registered.contains("Escape"));
+    }
+}
@@ -0,0 +1,126 @@
+use std::time::Instant;
+use tauri::{Manager, Window, Wry};
+use crate::{Error, ManagerExt, WindowConfig};
+use crate::sequence::SequenceStep;
+use crate::shortcut_backend::{register_exclusive, shortcut_backend, ShortcutBackend};
+
+/// Registers the first step of the window's shortcut sequence. The other steps are only registered
+/// while the sequence is in progress, so that they don't take keys away from other apps
+pub(crate) fn register_shortcut_sequence(window: &Window<Wry>, window_config: &WindowConfig) -> Result<(), Error> {
+    if window.app_handle().spotlight().shortcuts_suspended() {
+        return Ok(());
+    }
+    let first = match window_config.shortcut_sequence.as_ref().and_then(|sequence| sequence.first()) {
+        Some(first) => first.clone(),
+        None => return Ok(()),
+    };
+    let w = window.clone();
+    let accelerator = first.clone();
+    register_exclusive(
+        &mut shortcut_backend(&window.app_handle()),
+        window.label(),
+        &first,
+        Box::new(move || press_step(&w, &accelerator)),
+    )
+}
+
+pub(crate) fn unregister_shortcut_sequence(window: &Window<Wry>, window_config: &WindowConfig) -> Result<(), Error> {
+    let sequence = match &window_config.shortcut_sequence {
+        Some(sequence) => sequence,
+        None => return Ok(()),
+    };
+    window.app_handle().spotlight().shortcut_sequences().forget(window.label())?;
+    let mut shortcut_manager = shortcut_backend(&window.app_handle());
+    for step in sequence {
+        if ShortcutBackend::is_registered(&shortcut_manager, step).map_err(Error::query_failed(step))? {
+            ShortcutBackend::unregister(&mut shortcut_manager, step).map_err(Error::unregister_failed(step))?;
+            log_debug!("unregistered shortcut sequence step {} of spotlight window {}", step, window.label());
+        }
+    }
+    Ok(())
+}
+
+fn press_step(window: &Window<Wry>, accelerator: &str) {
+    // Shortcut handlers run while the shortcut manager is locked, so registering the other steps
+    // has to wait until the handler has returned. Tasks posted from the main thread run right away
+    let w = window.clone();
+    let accelerator = accelerator.to_string();
+    std::thread::spawn(move || {
+        let window = w.clone();
+        let result = w.run_on_main_thread(move || {
+            if let Err(err) = try_press_step(&window, &accelerator) {
+                log_error!("failed to handle shortcut sequence of spotlight window {}: {}", window.label(), err);
+            }
+        });
+        if let Err(err) = result {
+            log_error!("failed to handle shortcut sequence of spotlight window {}: {}", w.label(), err);
+        }
+    });
+}
+
+fn try_press_step(window: &Window<Wry>, accelerator: &str) -> Result<(), Error> {
+    let app_handle = window.app_handle();
+    let manager = app_handle.spotlight();
+    let window_config = manager.get_window_config(window)?.unwrap_or_default();
+    let sequence = window_config.shortcut_sequence.clone().unwrap_or_default();
+    let timeout = window_config.sequence_timeout();
+    match manager.shortcut_sequences().press(window.label(), &sequence, accelerator, timeout, Instant::now())? {
+        SequenceStep::Started | SequenceStep::Advanced => {
+            register_pending_steps(window, &sequence)?;
+            let w = window.clone();
+            std::thread::spawn(move || {
+                std::thread::sleep(timeout);
+                let window = w.clone();
+                let result = w.run_on_main_thread(move || {
+                    let app_handle = window.app_handle();
+                    let expired = app_handle.spotlight().shortcut_sequences().expire(window.label(), Instant::now());
+                    let result = expired.and_then(|expired| if expired { unregister_pending_steps(&window, &sequence) } else { Ok(()) });
+                    if let Err(err) = result {
+                        log_error!("failed to reset shortcut sequence of spotlight window {}: {}", window.label(), err);
+                    }
+                });
+                if let Err(err) = result {
+                    log_error!("failed to reset shortcut sequence of spotlight window {}: {}", w.label(), err);
+                }
+            });
+        }
+        SequenceStep::Completed => {
+            unregister_pending_steps(window, &sequence)?;
+            manager.toggle_from_shortcut(window);
+        }
+        SequenceStep::Reset => unregister_pending_steps(window, &sequence)?,
+    }
+    Ok(())
+}
+
+/// Registers the steps after the first one, so that the rest of the sequence can be pressed
+fn register_pending_steps(window: &Window<Wry>, sequence: &[String]) -> Result<(), Error> {
+    let mut shortcut_manager = shortcut_backend(&window.app_handle());
+    for step in sequence.iter().skip(1) {
+        if sequence.first() == Some(step) {
+            continue;
+        }
+        if !ShortcutBackend::is_registered(&shortcut_manager, step).map_err(Error::query_failed(step))? {
+            let w = window.clone();
+            let accelerator = step.clone();
+            ShortcutBackend::register(&mut shortcut_manager, step, Box::new(move || press_step(&w, &accelerator)))
+                .map_err(Error::register_failed(step))?;
+            log_debug!("registered shortcut sequence step {} of spotlight window {}", step, window.label());
+        }
+    }
+    Ok(())
+}
+
+fn unregister_pending_steps(window: &Window<Wry>, sequence: &[String]) -> Result<(), Error> {
+    let mut shortcut_manager = shortcut_backend(&window.app_handle());
+    for step in sequence.iter().skip(1) {
+        if sequence.first() == Some(step) {
+            continue;
+        }
+        if ShortcutBackend::is_registered(&shortcut_manager, step).map_err(Error::query_failed(step))? {
+            ShortcutBackend::unregister(&mut shortcut_manager, step).map_err(Error::unregister_failed(step))?;
+            log_debug!("unregistered shortcut sequence step {} of spotlight window {}", step, window.label());
+        }
+    }
+    Ok(())
+}
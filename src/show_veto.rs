@@ -0,0 +1,79 @@
+use std::{collections::HashSet, fmt, sync::{Mutex, RwLock}};
+use crate::Error;
+
+type Veto = Box<dyn Fn(&str) -> bool + Send + Sync>;
+
+/// Decides whether hidden windows may be shown. Showing happens synchronously, so there is no time
+/// to ask the frontend: it blocks windows ahead of time instead, and Rust code registers predicates
+#[derive(Default)]
+pub(crate) struct ShowVetoes {
+    vetoes: RwLock<Vec<Veto>>,
+    blocked: Mutex<HashSet<String>>,
+}
+
+impl fmt::Debug for ShowVetoes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ShowVetoes").field("blocked", &self.blocked).finish_non_exhaustive()
+    }
+}
+
+impl ShowVetoes {
+    /// Adds a predicate that is given the label of the window about to be shown and returns `true`
+    /// to keep it hidden
+    pub(crate) fn add(&self, veto: Veto) -> Result<(), Error> {
+        let mut vetoes = self.vetoes.write().map_err(|_| Error::RwLock(String::from("failed to write show vetoes")))?;
+        vetoes.push(veto);
+        Ok(())
+    }
+
+    pub(crate) fn set_blocked(&self, label: &str, blocked: bool) -> Result<(), Error> {
+        let mut blocked_labels = self
+            .blocked
+            .lock()
+            .map_err(|_| Error::Mutex(String::from("failed to lock blocked windows")))?;
+        if blocked {
+            blocked_labels.insert(label.to_string());
+        } else {
+            blocked_labels.remove(label);
+        }
+        Ok(())
+    }
+
+    /// Returns `true` if the window is blocked or any predicate vetoes showing it
+    pub(crate) fn vetoes(&self, label: &str) -> Result<bool, Error> {
+        let blocked = self
+            .blocked
+            .lock()
+            .map_err(|_| Error::Mutex(String::from("failed to lock blocked windows")))?
+            .contains(label);
+        if blocked {
+            return Ok(true);
+        }
+        let vetoes = self.vetoes.read().map_err(|_| Error::RwLock(String::from("failed to read show vetoes")))?;
+        Ok(vetoes.iter().any(|veto| veto(label)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ShowVetoes;
+
+    #[test]
+    fn block_and_unblock_window() {
+        let vetoes = ShowVetoes::default();
+        vetoes.set_blocked("main", true).unwrap();
+        assert!(vetoes.vetoes("main").unwrap());
+        assert!(!vetoes.vetoes("foo").unwrap());
+        vetoes.set_blocked("main", false).unwrap();
+        assert!(!vetoes.vetoes("main").unwrap());
+    }
+
+    #[test]
+    fn any_predicate_vetoes() {
+        let vetoes = ShowVetoes::default();
+        vetoes.add(Box::new(|_| false)).unwrap();
+        vetoes.add(Box::new(|label| label == "main")).unwrap();
+        assert!(vetoes.vetoes("main").unwrap());
+        assert!(!vetoes.vetoes("foo").unwrap());
+    }
+}
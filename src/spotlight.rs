@@ -0,0 +1,1368 @@
+use std::{collections::HashSet, sync::{atomic::{AtomicBool, Ordering}, Mutex, RwLock}, time::{Duration, Instant}};
+use tauri::{
+    AppHandle, Manager, PhysicalPosition, PhysicalSize, Window, WindowEvent, Wry,
+};
+use crate::{BlurAction, CloseScope, PluginConfig, ToggleBehavior, WindowConfig, WindowPosition};
+use crate::Error;
+use crate::event::{
+    emit_global_close, emit_monitor_fallback, emit_pin_changed, emit_spotlight_event, SPOTLIGHT_DID_HIDE,
+    SPOTLIGHT_DID_SHOW, SPOTLIGHT_FLASH, SPOTLIGHT_SHORTCUT_TRIGGERED, SPOTLIGHT_WILL_SHOW, WINDOW_DID_RESIGN_KEY,
+};
+use crate::accelerator::{same_accelerator, validate_accelerator};
+use crate::animation::{self, Animations};
+use crate::background_effect::apply_background_effect;
+use crate::debounce::{ShowCooldown, ToggleDebouncer};
+use crate::focus::focus_element;
+use crate::idle::IdleTimers;
+use crate::idle_hide::restart_idle_timer;
+use crate::linked::{self, linked_windows};
+use crate::pending_input::capture_pending_input;
+use crate::restore::restore_normal_state;
+use crate::shortcut_backend::{register_exclusive, register_replacing, shortcut_backend, ShortcutBackend, ShortcutHandler};
+use crate::shortcut_holders::ShortcutHolders;
+use crate::show_veto::ShowVetoes;
+use crate::custom_position::CustomPosition;
+use crate::previous_focus::PreviousFocus;
+use crate::state::{is_on_screen, TargetMonitor};
+use crate::sequence::ShortcutSequences;
+use crate::cycle::CycleGroups;
+use crate::cycle_shortcut::{register_cycle_shortcuts, unregister_cycle_shortcuts};
+use crate::shortcut_sequence::{register_shortcut_sequence, unregister_shortcut_sequence};
+use crate::registered_windows::RegisteredWindows;
+use crate::window_lock::WindowLocks;
+use crate::persistence::{remember_window_position, restore_window_position, save_window_position};
+use crate::app_focus::{app_has_focus, AppFocus};
+use crate::constrain::{constrain_position, constrain_window_to_monitors};
+use crate::prevent_close::hide_instead_of_closing;
+use crate::dock::{docked_position, docked_windows, follow_anchor, keep_docked_windows_next_to};
+use crate::position::next_monitor_index;
+use crate::platform::{
+    capture_previous_focus, is_laid_out, position_window_by_preset, position_window_on_its_monitor,
+    position_window_on_monitor, preset_target_monitor, restore_previous_focus, target_monitor_at,
+    target_monitor_of_window, NativeWindows, DETECTS_OUTSIDE_CLICKS,
+};
+
+#[derive(Default, Debug)]
+pub struct SpotlightManager {
+    config: RwLock<PluginConfig>,
+    registered_window: RegisteredWindows,
+    /// What the platform keeps for the registered windows, e.g. their panels on macOS
+    native: NativeWindows,
+    toggle_debouncer: ToggleDebouncer,
+    show_cooldown: ShowCooldown,
+    window_locks: WindowLocks,
+    animations: Animations,
+    app_focus: AppFocus,
+    /// Labels of the windows whose event handlers are attached
+    attached_windows: Mutex<HashSet<String>>,
+    close_shortcut_holders: ShortcutHolders,
+    toggle_shortcut_holders: ShortcutHolders,
+    show_vetoes: ShowVetoes,
+    custom_position: CustomPosition,
+    previous_focus: PreviousFocus,
+    shortcut_sequences: ShortcutSequences,
+    cycle_groups: CycleGroups,
+    /// Labels of the windows that don't hide on blur or outside clicks for the time being
+    auto_hide_suspended: Mutex<HashSet<String>>,
+    pinned: Mutex<HashSet<String>>,
+    idle_timers: IdleTimers,
+    idle_fade_timers: IdleTimers,
+    /// Whether every shortcut is unregistered until `resume_all_shortcuts`
+    shortcuts_suspended: AtomicBool,
+}
+
+impl SpotlightManager {
+    pub fn new(config: PluginConfig) -> Self {
+        Self {
+            config: RwLock::new(config),
+            ..Default::default()
+        }
+    }
+
+    pub(crate) fn animations(&self) -> &Animations {
+        &self.animations
+    }
+
+    /// Which window of the app has focus, as recorded from the focus events
+    pub(crate) fn app_focus(&self) -> &AppFocus {
+        &self.app_focus
+    }
+
+    /// What the platform keeps for the registered windows, for callbacks of the OS to look at
+    #[cfg(any(target_os = "macos", target_os = "windows"))]
+    pub(crate) fn native_windows(&self) -> &NativeWindows {
+        &self.native
+    }
+
+    /// Returns a snapshot of the current plugin config
+    pub fn config(&self) -> Result<PluginConfig, Error> {
+        let config = self.config.read().map_err(|_| Error::RwLock(String::from("failed to read config")))?;
+        Ok(config.clone())
+    }
+
+    pub(crate) fn get_window_config(&self, window: &Window<Wry>) -> Result<Option<WindowConfig>, Error> {
+        let config = self.config.read().map_err(|_| Error::RwLock(String::from("failed to read config")))?;
+        if let Some(window_configs) = &config.windows {
+            for window_config in window_configs {
+                if window.label() == window_config.label {
+                    return Ok(Some(window_config.clone()));
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    /// Replaces the config of the window with the same label, or adds it if there is none
+    pub(crate) fn set_window_config(&self, window_config: WindowConfig) -> Result<(), Error> {
+        let mut config = self.config.write().map_err(|_| Error::RwLock(String::from("failed to write config")))?;
+        let window_configs = config.windows.get_or_insert_with(Vec::new);
+        match window_configs.iter_mut().find(|config| config.label == window_config.label) {
+            Some(config) => *config = window_config,
+            None => window_configs.push(window_config),
+        }
+        Ok(())
+    }
+
+    /// Removes the config of the window with the given label
+    pub(crate) fn remove_window_config(&self, label: &str) -> Result<(), Error> {
+        let mut config = self.config.write().map_err(|_| Error::RwLock(String::from("failed to write config")))?;
+        if let Some(window_configs) = &mut config.windows {
+            window_configs.retain(|window_config| window_config.label != label);
+        }
+        Ok(())
+    }
+
+    pub fn is_registered(&self, label: &str) -> bool {
+        self.registered_window.contains(label)
+    }
+
+    /// Forgets a registered window that doesn't exist anymore
+    pub(crate) fn prune_window(&self, label: &str) -> Result<(), Error> {
+        self.native.forget(label)?;
+        self.registered_window.remove(label);
+        Ok(())
+    }
+
+    /// Unregisters the shortcuts and watchers of a spotlight window and forgets it, so that it
+    /// behaves like a regular window until it is registered again. Its config is kept. The global
+    /// close shortcut is only unregistered along with the last spotlight window
+    pub fn unregister_spotlight_window(&self, window: &Window<Wry>) -> Result<(), Error> {
+        if !self.is_registered(window.label()) {
+            return Ok(());
+        }
+        // Suspended shortcuts are unregistered already, and may have been taken by someone else
+        if !self.shortcuts_suspended() {
+            self.unregister_all_shortcuts(window)?;
+        }
+        self.animations.forget(window.label())?;
+        self.resume_auto_hide(window)?;
+        self.idle_timers.disarm(window.label())?;
+        self.idle_fade_timers.disarm(window.label())?;
+        self.pinned
+            .lock()
+            .map_err(|_| Error::Mutex(String::from("failed to lock pinned windows")))?
+            .remove(window.label());
+        self.previous_focus.take(window.label())?;
+        self.custom_position.forget(window.label())?;
+        self.prune_window(window.label())?;
+        Ok(())
+    }
+
+    /// Unregisters the toggle shortcuts, shortcut sequence and close shortcut of the window, and
+    /// lets go of the shared global shortcuts for it
+    fn unregister_all_shortcuts(&self, window: &Window<Wry>) -> Result<(), Error> {
+        if let Some(window_config) = self.get_window_config(window)? {
+            let shortcut_manager = shortcut_backend(&window.app_handle());
+            let mut shortcuts = vec![];
+            for shortcut in window_config.all_shortcuts() {
+                if shortcut_manager.is_registered(&shortcut).map_err(Error::query_failed(&shortcut))? {
+                    shortcuts.push(shortcut);
+                }
+            }
+            unregister_shortcuts(window, &shortcuts)?;
+            unregister_shortcut_sequence(window, &window_config)?;
+        }
+        unregister_window_close_shortcut(window)?;
+        unregister_close_shortcut(window)?;
+        unregister_toggle_all_shortcut(window)?;
+        unregister_cycle_shortcuts(window)
+    }
+
+    /// Unregisters every shortcut of the spotlight windows, e.g. while the app records a new
+    /// hotkey, until `resume_all_shortcuts` is called. Nothing registers any of them in the
+    /// meantime, changing a shortcut only updates the config. Suspending twice does nothing
+    pub fn suspend_all_shortcuts(&self, app_handle: &AppHandle<Wry>) -> Result<(), Error> {
+        if self.shortcuts_suspended.swap(true, Ordering::SeqCst) {
+            return Ok(());
+        }
+        log_debug!("suspending spotlight shortcuts");
+        self.for_each_window(app_handle, |window| self.unregister_all_shortcuts(window))
+    }
+
+    /// Registers the shortcuts of the spotlight windows again as they are configured now. Those
+    /// that got registered by someone else in the meantime are skipped with a warning. Resuming
+    /// shortcuts that aren't suspended does nothing
+    pub fn resume_all_shortcuts(&self, app_handle: &AppHandle<Wry>) -> Result<(), Error> {
+        if !self.shortcuts_suspended.swap(false, Ordering::SeqCst) {
+            return Ok(());
+        }
+        log_debug!("resuming spotlight shortcuts");
+        let config = self.config()?;
+        let shortcut_manager = shortcut_backend(app_handle);
+        let taken = |shortcut: Option<&String>| -> Result<bool, Error> {
+            let shortcut = match shortcut {
+                Some(shortcut) => shortcut,
+                None => return Ok(false),
+            };
+            let taken = shortcut_manager.is_registered(shortcut).map_err(Error::query_failed(shortcut))?;
+            if taken {
+                log_warn!("not resuming shortcut {}, it was registered elsewhere while suspended", shortcut);
+            }
+            Ok(taken)
+        };
+        // Shared by several windows, so checked before the first window takes them
+        let close_taken = taken(config.global_close_shortcut.as_ref())?;
+        let toggle_taken = taken(config.global_toggle_shortcut.as_ref())?;
+        let mut taken_cycle_shortcuts = vec![];
+        for group in config.cycle_groups.iter().flatten() {
+            if taken(Some(&group.shortcut))? {
+                taken_cycle_shortcuts.push(group.shortcut.clone());
+            }
+        }
+        self.for_each_window(app_handle, |window| {
+            let window_config = self.get_window_config(window)?.unwrap_or_default();
+            if shortcuts_active(window, &window_config) {
+                for shortcut in window_config.all_shortcuts() {
+                    if let Err(err) = register_shortcut_for_window(window, &shortcut) {
+                        log_warn!("not resuming shortcut {} of spotlight window {}: {}", shortcut, window.label(), err);
+                    }
+                }
+            }
+            if let Err(err) = register_shortcut_sequence(window, &window_config) {
+                log_warn!("not resuming shortcut sequence of spotlight window {}: {}", window.label(), err);
+            }
+            if !close_taken {
+                register_close_shortcut(window)?;
+            }
+            if !toggle_taken {
+                register_toggle_all_shortcut(window)?;
+            }
+            register_cycle_shortcuts(window, &taken_cycle_shortcuts)?;
+            if window.is_focused()? {
+                register_window_close_shortcut(window)?;
+            }
+            Ok(())
+        })
+    }
+
+    pub(crate) fn shortcuts_suspended(&self) -> bool {
+        self.shortcuts_suspended.load(Ordering::SeqCst)
+    }
+
+    /// Attaches the event handlers of the window, unless they are still attached from a previous
+    /// registration of the same window
+    fn attach_event_handlers(&self, window: &Window<Wry>) -> Result<(), Error> {
+        let mut attached = self
+            .attached_windows
+            .lock()
+            .map_err(|_| Error::Mutex(String::from("failed to lock attached windows")))?;
+        if attached.insert(window.label().to_string()) {
+            handle_focus_state_change(window);
+            handle_window_destroyed(window);
+            remember_window_position(window);
+            constrain_window_to_monitors(window);
+            hide_instead_of_closing(window);
+            keep_docked_windows_next_to(window);
+        }
+        Ok(())
+    }
+
+    /// Forgets the event handlers of a destroyed window, a new window with its label needs its own
+    fn forget_event_handlers(&self, label: &str) -> Result<(), Error> {
+        let mut attached = self
+            .attached_windows
+            .lock()
+            .map_err(|_| Error::Mutex(String::from("failed to lock attached windows")))?;
+        attached.remove(label);
+        Ok(())
+    }
+
+    pub fn init_spotlight_window(&self, window: &Window<Wry>) -> Result<(), Error> {
+        let window_config = match self.get_window_config(window)? {
+            Some(window_config) => window_config,
+            None => return Ok(()),
+        };
+        if window_config.should_start_hidden() {
+            // Before anything else, so that the window is on screen as briefly as possible
+            window.hide().map_err(|_| Error::FailedToHideWindow)?;
+        }
+        self.registered_window.init(
+            window.label(),
+            || {
+                self.set_up_spotlight_window(window, &window_config).or_else(|err| {
+                    self.native.forget(window.label())?;
+                    Err(err)
+                })
+            },
+            || self.register_again(window, &window_config),
+        )
+    }
+
+    fn set_up_spotlight_window(&self, window: &Window<Wry>, window_config: &WindowConfig) -> Result<(), Error> {
+        self.config()?.check_shortcut_conflict(window_config)?;
+        if let Some(effect) = window_config.background_effect {
+            apply_background_effect(window, effect)?;
+        }
+        self.native.set_up(window, window_config)?;
+        if window_config.should_ignore_cursor_events() {
+            window.set_ignore_cursor_events(true)?;
+        }
+        if shortcuts_active(window, window_config) {
+            register_shortcuts_for_window(window, window_config)?;
+        }
+        register_close_shortcut(window)?;
+        register_toggle_all_shortcut(window)?;
+        register_cycle_shortcuts(window, &[])?;
+        register_shortcut_sequence(window, window_config)?;
+        self.attach_event_handlers(window)?;
+        if window_config.should_remember_position() {
+            if let Err(err) = restore_window_position(window) {
+                log_warn!("failed to restore position of spotlight window {}: {}", window.label(), err);
+            }
+        }
+        Ok(())
+    }
+
+    /// Moves the shortcuts of a window that is registered already over to it, e.g. after it was
+    /// recreated with the same label, instead of registering them twice. The shared shortcuts don't
+    /// depend on the window, the window it registered them for holds them still
+    fn register_again(&self, window: &Window<Wry>, window_config: &WindowConfig) -> Result<(), Error> {
+        log_debug!("spotlight window {} is registered already, moving its shortcuts over", window.label());
+        if shortcuts_active(window, window_config) {
+            for shortcut in window_config.all_shortcuts() {
+                replace_shortcut_for_window(window, &shortcut)?;
+            }
+        }
+        if !self.shortcuts_suspended() {
+            unregister_shortcut_sequence(window, window_config)?;
+            register_shortcut_sequence(window, window_config)?;
+        }
+        self.attach_event_handlers(window)
+    }
+
+    /// Registers the configured windows that exist but aren't registered yet, e.g. because they were
+    /// created before the plugin was set up, and returns the labels of those that don't exist yet.
+    /// Those are registered as soon as their webview is created
+    pub fn register_all_configured(&self, app_handle: &AppHandle<Wry>) -> Result<Vec<String>, Error> {
+        let mut missing = vec![];
+        for window_config in self.config()?.windows.unwrap_or_default() {
+            match app_handle.get_window(&window_config.label) {
+                Some(window) => self.init_spotlight_window(&window)?,
+                None => missing.push(window_config.label),
+            }
+        }
+        Ok(missing)
+    }
+
+    /// Registers a window that isn't part of the plugin config, e.g. one created after startup.
+    /// `window_config.label` is replaced by the label of the window
+    pub fn register_spotlight_window(&self, window: &Window<Wry>, mut window_config: WindowConfig) -> Result<(), Error> {
+        if self.is_registered(window.label()) {
+            return Err(Error::AlreadyRegistered(window.label().to_string()));
+        }
+        window_config.label = window.label().to_string();
+        let previous_config = self.get_window_config(window)?;
+        self.set_window_config(window_config)?;
+        let result = self.init_spotlight_window(window);
+        if result.is_err() {
+            match previous_config {
+                Some(previous_config) => self.set_window_config(previous_config)?,
+                None => self.remove_window_config(window.label())?,
+            }
+        }
+        result
+    }
+
+    /// Replaces the toggle shortcut of a spotlight window, nothing is changed if it fails
+    pub fn update_shortcut(&self, window: &Window<Wry>, shortcut: &str) -> Result<(), Error> {
+        let mut window_config = self
+            .get_window_config(window)?
+            .ok_or_else(|| Error::UnregisteredWindow(window.label().to_string()))?;
+        if same_accelerator(&window_config.shortcut, shortcut) {
+            return Ok(());
+        }
+        if let Some(owner) = self.config()?.shortcut_owner(shortcut, window.label()) {
+            return Err(Error::ShortcutConflict(format!("{} is already used by {}", shortcut, owner)));
+        }
+        // `shortcut_owner` leaves out the window itself, whose other shortcuts would be registered twice
+        if window_config.all_shortcuts().iter().any(|s| same_accelerator(s, shortcut)) {
+            return Err(Error::ShortcutConflict(format!(
+                "{} is already one of the shortcuts of window {}",
+                shortcut,
+                window.label()
+            )));
+        }
+        let active = shortcuts_active(window, &window_config);
+        if active {
+            register_shortcut_for_window(window, shortcut)?;
+        }
+        let mut shortcut_manager = shortcut_backend(&window.app_handle());
+        if let Err(err) = unregister_if_registered(&mut shortcut_manager, &window_config.shortcut) {
+            // The new shortcut is given up again, so that the window keeps the shortcut it had
+            if active {
+                if let Err(undo_err) = shortcut_manager.unregister(shortcut) {
+                    log_error!("failed to unregister shortcut {} again: {}", shortcut, undo_err);
+                }
+            }
+            return Err(err);
+        }
+        window_config.shortcut = shortcut.to_string();
+        self.set_window_config(window_config)
+    }
+
+    /// Registers the toggle shortcuts of the spotlight windows that aren't `global` while the app
+    /// has focus, or unregisters them once it lost it
+    pub(crate) fn set_local_shortcuts_active(&self, app_handle: &AppHandle<Wry>, active: bool) -> Result<(), Error> {
+        if self.shortcuts_suspended() {
+            return Ok(());
+        }
+        for window_config in self.config()?.windows.unwrap_or_default() {
+            if window_config.is_global() || !self.is_registered(&window_config.label) {
+                continue;
+            }
+            let window = match app_handle.get_window(&window_config.label) {
+                Some(window) => window,
+                None => continue,
+            };
+            let shortcut_manager = shortcut_backend(app_handle);
+            let mut registered = vec![];
+            for shortcut in window_config.all_shortcuts() {
+                if shortcut_manager.is_registered(&shortcut).map_err(Error::query_failed(&shortcut))? {
+                    registered.push(shortcut);
+                }
+            }
+            let result = if !active {
+                unregister_shortcuts(&window, &registered)
+            } else if registered.is_empty() {
+                register_shortcuts_for_window(&window, &window_config)
+            } else {
+                Ok(())
+            };
+            if let Err(err) = result {
+                log_error!("failed to update local shortcuts of spotlight window {}: {}", window.label(), err);
+            }
+        }
+        Ok(())
+    }
+
+    /// Replaces the global close shortcut, or removes it with `None`. Nothing is changed if the new
+    /// one is malformed, conflicts with a toggle shortcut or fails to register
+    pub fn set_global_close_shortcut(&self, app_handle: &AppHandle<Wry>, close_shortcut: Option<&str>) -> Result<(), Error> {
+        let config = self.config()?;
+        if let Some(close_shortcut) = close_shortcut {
+            validate_accelerator(close_shortcut)?;
+            config.check_global_close_conflict(close_shortcut)?;
+        }
+        let mut shortcut_manager = shortcut_backend(app_handle);
+        match (config.global_close_shortcut.as_deref(), close_shortcut) {
+            (Some(old), Some(new)) if same_accelerator(old, new) => return Ok(()),
+            (Some(old), Some(new)) => {
+                self.close_shortcut_holders.replace_shortcut(&mut shortcut_manager, old, new, global_close_handler(app_handle))?;
+            }
+            // Every spotlight window lets go of it while the config still names it
+            (Some(_), None) => self.for_each_window(app_handle, unregister_close_shortcut)?,
+            (None, _) => {}
+        }
+        self.set_global_close_shortcut_config(close_shortcut)?;
+        if config.global_close_shortcut.is_none() && close_shortcut.is_some() {
+            // Every spotlight window takes hold of it once the config names it
+            if let Err(err) = self.for_each_window(app_handle, register_close_shortcut) {
+                self.for_each_window(app_handle, unregister_close_shortcut)?;
+                self.set_global_close_shortcut_config(None)?;
+                return Err(err);
+            }
+        }
+        Ok(())
+    }
+
+    fn set_global_close_shortcut_config(&self, close_shortcut: Option<&str>) -> Result<(), Error> {
+        let mut config = self.config.write().map_err(|_| Error::RwLock(String::from("failed to write config")))?;
+        config.global_close_shortcut = close_shortcut.map(String::from);
+        Ok(())
+    }
+
+    /// Turns hiding the window when it loses focus on or off, taking effect on the next focus loss.
+    /// Replaces the `on_blur` action of the window
+    pub fn set_auto_hide(&self, window: &Window<Wry>, auto_hide: bool) -> Result<(), Error> {
+        let mut window_config = self
+            .get_window_config(window)?
+            .ok_or_else(|| Error::UnregisteredWindow(window.label().to_string()))?;
+        window_config.auto_hide = Some(auto_hide);
+        window_config.on_blur = None;
+        self.set_window_config(window_config)?;
+        self.native.set_auto_hide(window.label(), auto_hide)
+    }
+
+    /// Lets clicks pass through the window, or stops doing so
+    pub fn set_ignore_cursor_events(&self, window: &Window<Wry>, ignore: bool) -> Result<(), Error> {
+        let mut window_config = self
+            .get_window_config(window)?
+            .ok_or_else(|| Error::UnregisteredWindow(window.label().to_string()))?;
+        window.set_ignore_cursor_events(ignore)?;
+        window_config.ignore_cursor_events = Some(ignore);
+        self.set_window_config(window_config)
+    }
+
+    pub(crate) fn idle_timers(&self) -> &IdleTimers {
+        &self.idle_timers
+    }
+
+    pub(crate) fn idle_fade_timers(&self) -> &IdleTimers {
+        &self.idle_fade_timers
+    }
+
+    /// Starts the `auto_hide_after_ms` and `idle_fade` timers of a visible window over, e.g. when the
+    /// user is active in it, bringing it back to full opacity if it faded
+    pub fn reset_idle_timer(&self, window: &Window<Wry>) -> Result<(), Error> {
+        if window.is_visible().map_err(|_| Error::FailedToCheckWindowVisibility)? {
+            restart_idle_timer(window)?;
+        }
+        Ok(())
+    }
+
+    /// Toggles the window as its shortcuts do, dropping toggles within the debounce
+    pub(crate) fn toggle_from_shortcut(&self, window: &Window<Wry>) {
+        log_debug!("toggle shortcut of spotlight window {} pressed", window.label());
+        // Emitted for every press, even those that end up doing nothing
+        if let Err(err) = emit_spotlight_event(window, SPOTLIGHT_SHORTCUT_TRIGGERED) {
+            log_error!("failed to emit shortcut press of spotlight window {}: {}", window.label(), err);
+        }
+        if let Err(err) = self.toggle(window) {
+            log_error!("failed to toggle spotlight window {}: {}", window.label(), err);
+        }
+    }
+
+    /// Hides the window if it is visible and shows it otherwise, the way its shortcuts do.
+    /// Toggles within the debounce of the previous one are dropped, and a focused window flashes
+    /// instead of hiding if its toggle behavior says so. A window that is fading out is shown
+    /// again
+    pub fn toggle(&self, window: &Window<Wry>) -> Result<(), Error> {
+        if !self.accept_toggle(window)? {
+            return Ok(());
+        }
+        let visible = window.is_visible().map_err(|_| Error::FailedToCheckWindowVisibility)?;
+        if visible && !animation::is_hiding(window) {
+            self.hide_or_flash(window)
+        } else {
+            self.show(window)
+        }
+    }
+
+    /// Hides the visible window, unless it has focus and its toggle behavior is to flash
+    fn hide_or_flash(&self, window: &Window<Wry>) -> Result<(), Error> {
+        let toggle_behavior = self.get_window_config(window)?.unwrap_or_default().toggle_behavior();
+        if toggle_behavior == ToggleBehavior::FlashWhenFocused && window.is_focused()? {
+            return emit_spotlight_event(window, SPOTLIGHT_FLASH);
+        }
+        self.hide(window)
+    }
+
+    pub(crate) fn shortcut_sequences(&self) -> &ShortcutSequences {
+        &self.shortcut_sequences
+    }
+
+    pub(crate) fn cycle_groups(&self) -> &CycleGroups {
+        &self.cycle_groups
+    }
+
+    /// Returns `false` if the window was toggled too recently to be toggled again
+    fn accept_toggle(&self, window: &Window<Wry>) -> Result<bool, Error> {
+        let debounce = self.get_window_config(window)?.unwrap_or_default().toggle_debounce();
+        self.toggle_debouncer.accept(window.label(), debounce, Instant::now())
+    }
+
+    /// Hides every spotlight window if any is visible, otherwise shows the first registered one in
+    /// the config
+    pub(crate) fn toggle_all(&self, app_handle: &AppHandle<Wry>) -> Result<(), Error> {
+        let mut windows = vec![];
+        for window_config in self.config()?.windows.unwrap_or_default() {
+            if self.is_registered(&window_config.label) {
+                if let Some(window) = app_handle.get_window(&window_config.label) {
+                    windows.push(window);
+                }
+            }
+        }
+        let mut any_visible = false;
+        for window in &windows {
+            // A window that is fading out already counts as hidden
+            any_visible |= window.is_visible().map_err(|_| Error::FailedToCheckWindowVisibility)? && !animation::is_hiding(window);
+        }
+        if any_visible {
+            for window in &windows {
+                self.hide(window)?;
+            }
+        } else if let Some(window) = windows.first() {
+            self.show(window)?;
+        }
+        Ok(())
+    }
+
+    /// Unregisters every spotlight window along with all shortcuts of the plugin, e.g. before the
+    /// plugin is unloaded, so that loading it again can register them. Windows that don't exist
+    /// anymore are forgotten, and configs are kept for windows registered again
+    pub fn shutdown(&self, app_handle: &AppHandle<Wry>) -> Result<(), Error> {
+        log_debug!("shutting down spotlight windows");
+        self.for_each_window(app_handle, |window| self.unregister_spotlight_window(window))
+    }
+
+    /// Hides every spotlight window, trying all of them even if some fail
+    pub fn hide_all(&self, app_handle: &AppHandle<Wry>) -> Result<(), Error> {
+        self.for_each_window(app_handle, |window| self.hide(window))
+    }
+
+    /// Hides every spotlight window without sliding any of them off screen
+    fn hide_all_without_sliding(&self, app_handle: &AppHandle<Wry>) -> Result<(), Error> {
+        self.for_each_window(app_handle, |window| self.hide_with_linked(window, false))
+    }
+
+    /// Shows every spotlight window, trying all of them even if some fail
+    pub fn show_all(&self, app_handle: &AppHandle<Wry>) -> Result<(), Error> {
+        self.for_each_window(app_handle, |window| self.show(window))
+    }
+
+    /// Returns the labels of the spotlight windows that are visible
+    pub(crate) fn visible_labels(&self, app_handle: &AppHandle<Wry>) -> Result<Vec<String>, Error> {
+        let mut labels = vec![];
+        self.for_each_window(app_handle, |window| {
+            if window.is_visible().map_err(|_| Error::FailedToCheckWindowVisibility)? {
+                labels.push(window.label().to_string());
+            }
+            Ok(())
+        })?;
+        Ok(labels)
+    }
+
+    /// Returns the configs of the registered spotlight windows, including those registered at
+    /// runtime
+    pub fn registered_window_configs(&self, app_handle: &AppHandle<Wry>) -> Result<Vec<WindowConfig>, Error> {
+        let mut window_configs = vec![];
+        self.for_each_window(app_handle, |window| {
+            if let Some(window_config) = self.get_window_config(window)? {
+                window_configs.push(window_config);
+            }
+            Ok(())
+        })?;
+        Ok(window_configs)
+    }
+
+    /// Runs `f` on every spotlight window, forgetting those that don't exist anymore, and collects
+    /// the errors along with the labels of the windows they happened for
+    fn for_each_window<F: FnMut(&Window<Wry>) -> Result<(), Error>>(&self, app_handle: &AppHandle<Wry>, mut f: F) -> Result<(), Error> {
+        for label in self.registered_window.prune(|label| app_handle.get_window(label).is_some()) {
+            log_debug!("forgot spotlight window {}, which doesn't exist anymore", label);
+            self.native.forget(&label)?;
+        }
+        let mut errors = vec![];
+        for label in self.registered_window.labels() {
+            if let Some(window) = app_handle.get_window(&label) {
+                if let Err(err) = f(&window) {
+                    errors.push((label, err));
+                }
+            }
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::WindowsFailed(errors))
+        }
+    }
+
+    pub fn show(&self, window: &Window<Wry>) -> Result<(), Error> {
+        self.show_window(window, true, None)?;
+        self.show_linked(window)
+    }
+
+    /// Shows the window on top without taking focus from the app the user is in, e.g. for a HUD
+    pub fn show_without_focus(&self, window: &Window<Wry>) -> Result<(), Error> {
+        self.show_window(window, true, Some(false))?;
+        self.show_linked(window)
+    }
+
+    /// Shows the windows linked to a shown window, without taking focus from it
+    fn show_linked(&self, window: &Window<Wry>) -> Result<(), Error> {
+        // Nothing comes along if showing the window was vetoed
+        if !window.is_visible().map_err(|_| Error::FailedToCheckWindowVisibility)? {
+            return Ok(());
+        }
+        for linked in linked_windows(window)? {
+            self.show_window(&linked, true, Some(false))?;
+        }
+        Ok(())
+    }
+
+    /// Moves the window onto the monitor at `monitor_index` in `available_monitors` and shows it
+    /// there, laid out by its position preset, no matter where the cursor is
+    pub fn show_on_monitor(&self, window: &Window<Wry>, monitor_index: usize) -> Result<(), Error> {
+        let _lock = self.window_locks.lock(window.label())?;
+        let monitors = window.available_monitors().map_err(Error::MonitorQueryFailed)?;
+        let monitor = monitors
+            .get(monitor_index)
+            .ok_or(Error::MonitorIndexOutOfRange(monitor_index, monitors.len()))?;
+        let window_config = self.get_window_config(window)?.unwrap_or_default();
+        restore_normal_state(window)?;
+        position_window_on_monitor(window, monitor, window_config.placement())?;
+        self.show_window(window, false, None)
+    }
+
+    /// Moves a visible window onto the next monitor in `available_monitors`, the last one wrapping
+    /// around to the first, and lays it out there by its position preset. Hidden windows stay
+    /// where they are
+    pub fn move_to_next_monitor(&self, window: &Window<Wry>) -> Result<(), Error> {
+        let _lock = self.window_locks.lock(window.label())?;
+        if !window.is_visible().map_err(|_| Error::FailedToCheckWindowVisibility)? {
+            return Ok(());
+        }
+        let monitors = window.available_monitors().map_err(Error::MonitorQueryFailed)?;
+        let current = window.current_monitor().map_err(Error::MonitorQueryFailed)?.and_then(|current| {
+            monitors
+                .iter()
+                .position(|monitor| monitor.name() == current.name() && monitor.position() == current.position())
+        });
+        let monitor = match next_monitor_index(current, monitors.len()) {
+            Some(next) => &monitors[next],
+            None => return Ok(()),
+        };
+        let window_config = self.get_window_config(window)?.unwrap_or_default();
+        position_window_on_monitor(window, monitor, window_config.placement())?;
+        if window_config.should_remember_position() {
+            save_window_position(window)?;
+        }
+        Ok(())
+    }
+
+    /// Resizes the window, e.g. as its content grows, and places it again as laid out by its
+    /// position preset on the monitor it is on, unless it stays where the user left it
+    pub fn set_size(&self, window: &Window<Wry>, size: PhysicalSize<u32>) -> Result<(), Error> {
+        let _lock = self.window_locks.lock(window.label())?;
+        let window_config = self.get_window_config(window)?.unwrap_or_default();
+        animation::cancel_resize(window)?;
+        window.set_size(size)?;
+        // A window shown at the cursor keeps its top-left corner there
+        let at_cursor = matches!(window_config.position, Some(WindowPosition::AtCursor { .. }));
+        if !window_config.should_remember_position() && !at_cursor && is_laid_out(&window_config) {
+            position_window_on_its_monitor(window, window_config.placement())?;
+        }
+        Ok(())
+    }
+
+    /// Smoothly resizes the window to the given height over the duration, e.g. as search results
+    /// stream in, keeping its width and top edge in place. A new call interrupts a running one
+    pub fn animate_height(&self, window: &Window<Wry>, height: u32, duration: Duration) -> Result<(), Error> {
+        let _lock = self.window_locks.lock(window.label())?;
+        animation::animate_height(window, height, duration)
+    }
+
+    /// Returns the monitor `show` would put the window on right now, along with its work area. A
+    /// window that stays where it is reports the monitor it is on, and one placed by
+    /// `with_position_fn` or `set_next_position` the monitor containing that position
+    pub fn target_monitor(&self, window: &Window<Wry>) -> Result<Option<TargetMonitor>, Error> {
+        let window_config = self
+            .get_window_config(window)?
+            .ok_or_else(|| Error::UnregisteredWindow(window.label().to_string()))?;
+        let target_monitor = if let Some(position) = self.custom_position.next(window.label())? {
+            target_monitor_at(window, constrain_position(window, position)?)?
+        } else if window_config.should_remember_position() {
+            None
+        } else if let Some(position) = self.custom_position.resolve(window)? {
+            target_monitor_at(window, position)?
+        } else {
+            preset_target_monitor(window, &window_config, self.show_cooldown.shown_before(window.label())?)?
+        };
+        match target_monitor {
+            Some(target_monitor) => Ok(Some(target_monitor)),
+            None => target_monitor_of_window(window),
+        }
+    }
+
+    /// Places the window as laid out by its position preset on the monitor it is on, right away and
+    /// whether it is shown or not, e.g. after the user dragged it around. A window that remembers
+    /// its position comes back there the next time it is shown
+    pub fn recenter(&self, window: &Window<Wry>) -> Result<(), Error> {
+        let _lock = self.window_locks.lock(window.label())?;
+        let window_config = self
+            .get_window_config(window)?
+            .ok_or_else(|| Error::UnregisteredWindow(window.label().to_string()))?;
+        position_window_on_its_monitor(window, window_config.placement())?;
+        if window_config.should_remember_position() {
+            // Hidden windows aren't guaranteed to report being moved
+            save_window_position(window)?;
+        }
+        Ok(())
+    }
+
+    /// Sets the opacity of the window, clamped to 0..1. Show and hide animations fade to it instead
+    /// of full opacity
+    pub fn set_opacity(&self, window: &Window<Wry>, opacity: f64) -> Result<(), Error> {
+        animation::set_rest_opacity(window, opacity)
+    }
+
+    /// Keeps the window from hiding when it loses focus or is clicked outside of, e.g. while a
+    /// native file dialog opened from it is up, until `resume_auto_hide` is called
+    pub fn suspend_auto_hide(&self, window: &Window<Wry>) -> Result<(), Error> {
+        let mut suspended = self
+            .auto_hide_suspended
+            .lock()
+            .map_err(|_| Error::Mutex(String::from("failed to lock suspended auto hide")))?;
+        suspended.insert(window.label().to_string());
+        Ok(())
+    }
+
+    pub fn resume_auto_hide(&self, window: &Window<Wry>) -> Result<(), Error> {
+        let mut suspended = self
+            .auto_hide_suspended
+            .lock()
+            .map_err(|_| Error::Mutex(String::from("failed to lock suspended auto hide")))?;
+        suspended.remove(window.label());
+        Ok(())
+    }
+
+    /// Whether the window was shown too recently to be hidden by focus loss
+    pub(crate) fn is_cooling_down(&self, window: &Window<Wry>) -> Result<bool, Error> {
+        let window_config = self.get_window_config(window)?.unwrap_or_default();
+        let cooldown = window_config.reopen_cooldown().max(window_config.min_visible());
+        self.show_cooldown.cooling_down(window.label(), cooldown, Instant::now())
+    }
+
+    /// Whether the window was shown too recently to be hidden by an outside click
+    #[cfg(any(target_os = "macos", target_os = "windows"))]
+    pub(crate) fn is_within_min_visible(&self, window: &Window<Wry>) -> Result<bool, Error> {
+        let min_visible = self.get_window_config(window)?.unwrap_or_default().min_visible();
+        self.show_cooldown.cooling_down(window.label(), min_visible, Instant::now())
+    }
+
+    /// Pins the window open, so that it no longer hides when it loses focus or is clicked outside
+    /// of until it is unpinned, e.g. to keep it around for reference while working in another app
+    pub fn set_pinned(&self, window: &Window<Wry>, pinned: bool) -> Result<(), Error> {
+        let changed = {
+            let mut pinned_labels = self
+                .pinned
+                .lock()
+                .map_err(|_| Error::Mutex(String::from("failed to lock pinned windows")))?;
+            if pinned {
+                pinned_labels.insert(window.label().to_string())
+            } else {
+                pinned_labels.remove(window.label())
+            }
+        };
+        if changed {
+            emit_pin_changed(window, pinned)?;
+        }
+        Ok(())
+    }
+
+    pub fn is_pinned(&self, window: &Window<Wry>) -> Result<bool, Error> {
+        let pinned = self
+            .pinned
+            .lock()
+            .map_err(|_| Error::Mutex(String::from("failed to lock pinned windows")))?;
+        Ok(pinned.contains(window.label()))
+    }
+
+    /// Whether auto-hide is suspended or the window is pinned
+    pub(crate) fn is_auto_hide_suspended(&self, label: &str) -> Result<bool, Error> {
+        let suspended = self
+            .auto_hide_suspended
+            .lock()
+            .map_err(|_| Error::Mutex(String::from("failed to lock suspended auto hide")))?
+            .contains(label);
+        let pinned = self
+            .pinned
+            .lock()
+            .map_err(|_| Error::Mutex(String::from("failed to lock pinned windows")))?
+            .contains(label);
+        Ok(suspended || pinned)
+    }
+
+    /// Computes where windows are shown instead of the configured position, whenever `f` returns a
+    /// position. It runs while the window is being shown, so it must not show or hide spotlight
+    /// windows itself
+    pub fn with_position_fn<F: Fn(&Window<Wry>) -> Option<PhysicalPosition<i32>> + Send + Sync + 'static>(&self, f: F) -> Result<(), Error> {
+        self.custom_position.set(Box::new(f))
+    }
+
+    /// Shows the window at `position`, in physical pixels, the next time it is shown instead of
+    /// where it would go otherwise, e.g. next to a right-clicked element. It is moved onto the
+    /// monitors if it would end up off them. Later shows place it as configured again
+    pub fn set_next_position(&self, window: &Window<Wry>, position: PhysicalPosition<i32>) -> Result<(), Error> {
+        if !self.is_registered(window.label()) {
+            return Err(Error::UnregisteredWindow(window.label().to_string()));
+        }
+        self.custom_position.set_next(window.label(), position)
+    }
+
+    /// Keeps hidden spotlight windows hidden whenever the predicate returns `true` for their label,
+    /// e.g. during a modal flow
+    pub fn add_show_veto<F: Fn(&str) -> bool + Send + Sync + 'static>(&self, veto: F) -> Result<(), Error> {
+        self.show_vetoes.add(Box::new(veto))
+    }
+
+    /// Blocks or unblocks showing the window, for vetoes coming from the frontend
+    pub fn set_show_blocked(&self, window: &Window<Wry>, blocked: bool) -> Result<(), Error> {
+        self.show_vetoes.set_blocked(window.label(), blocked)
+    }
+
+    /// Moves a window that is being shown to where it goes, keeping it on screen
+    fn place_window(&self, window: &Window<Wry>, window_config: &WindowConfig, reposition: bool, hiding: bool, was_hidden: bool, shown_before: bool) -> Result<(), Error> {
+        let next_position = if reposition && !hiding {
+            self.custom_position.take_next(window.label())?
+        } else {
+            None
+        };
+        let docked_position = if reposition && !hiding {
+            docked_position(window, window_config)?
+        } else {
+            None
+        };
+        if let Some(position) = next_position {
+            window.set_position(constrain_position(window, position)?)?;
+        } else if let Some(position) = docked_position {
+            window.set_position(position)?;
+        } else if !reposition || window_config.should_remember_position() || hiding {
+            // The window stays where it was put or where the user left it
+        } else if let Some(position) = self.custom_position.resolve(window)? {
+            window.set_position(position)?;
+        } else {
+            position_window_by_preset(window, window_config, shown_before)?;
+        }
+        if was_hidden && !hiding && !is_on_screen(window)? {
+            self.fall_back_to_primary_monitor(window, window_config)?;
+        }
+        Ok(())
+    }
+
+    /// Moves a window that would be shown off screen, e.g. on a monitor that was unplugged since it
+    /// was last shown, to the primary monitor
+    fn fall_back_to_primary_monitor(&self, window: &Window<Wry>, window_config: &WindowConfig) -> Result<(), Error> {
+        let monitor = match window.primary_monitor().map_err(Error::MonitorQueryFailed)? {
+            Some(monitor) => monitor,
+            None => return Ok(()),
+        };
+        log_warn!("spotlight window {} would be off screen, moving it to the primary monitor", window.label());
+        position_window_on_monitor(window, &monitor, window_config.placement())?;
+        emit_monitor_fallback(window, &monitor)
+    }
+
+    /// Shows the window, placing it as configured first unless `reposition` is `false`. It takes
+    /// focus unless `grab_focus` or, if it is `None`, the config says otherwise
+    fn show_window(&self, window: &Window<Wry>, reposition: bool, grab_focus: Option<bool>) -> Result<(), Error> {
+        let _lock = self.window_locks.lock(window.label())?;
+        let window_config = self.get_window_config(window)?.unwrap_or_default();
+        let grab_focus = grab_focus.unwrap_or_else(|| window_config.should_grab_focus());
+        // A window that is fading out is brought back from where it is
+        let hiding = animation::is_hiding(window);
+        let was_hidden = !window.is_visible().map_err(|_| Error::FailedToCheckWindowVisibility)?;
+        let mut shown_before = true;
+        if hiding || was_hidden {
+            if self.show_vetoes.vetoes(window.label())? {
+                log_info!("showing spotlight window {} was vetoed", window.label());
+                return Ok(());
+            }
+            log_debug!("showing spotlight window {}", window.label());
+            emit_spotlight_event(window, SPOTLIGHT_WILL_SHOW)?;
+            shown_before = self.show_cooldown.shown_before(window.label())?;
+            self.show_cooldown.record(window.label(), Instant::now())?;
+            if !hiding && grab_focus && window_config.should_restore_previous_focus() {
+                self.previous_focus.remember(window.label(), capture_previous_focus(window)?)?;
+            }
+            if !hiding && grab_focus && window_config.should_capture_pending_input() {
+                // Typing still works without it, the first characters may just get lost
+                if let Err(err) = capture_pending_input(window) {
+                    log_warn!("failed to capture pending input of spotlight window {}: {}", window.label(), err);
+                }
+            }
+        }
+        if !hiding {
+            // A minimized or fullscreen window can't be placed, and comes back in a weird state
+            restore_normal_state(window)?;
+        }
+        if let Some(size) = window_config.reset_size_on_show {
+            if !hiding && was_hidden {
+                window.set_size(size)?;
+            }
+        }
+        // A window that can't be placed is still shown, where it is
+        match self.place_window(window, &window_config, reposition, hiding, was_hidden, shown_before) {
+            Err(err @ Error::MonitorQueryFailed(_)) | Err(err @ Error::PositionResolutionFailed(_)) => {
+                log_warn!("failed to position spotlight window {}, showing it where it is: {}", window.label(), err);
+            }
+            result => result?,
+        }
+        // The window never counted as hidden while it was fading out
+        if self.native.show(window, &window_config, hiding, grab_focus)? {
+            emit_spotlight_event(window, SPOTLIGHT_DID_SHOW)?;
+        }
+        if was_hidden && !hiding {
+            // Windows docked to this one that were shown while it was hidden join it now
+            if let Err(err) = follow_anchor(window) {
+                log_warn!("failed to move windows docked to spotlight window {}: {}", window.label(), err);
+            }
+        }
+        if let Some(selector) = window_config.focus_selector.as_ref().filter(|_| grab_focus) {
+            if let Err(err) = focus_element(window, selector) {
+                log_warn!("failed to focus {} in spotlight window {}: {}", selector, window.label(), err);
+            }
+        }
+        restart_idle_timer(window)
+    }
+
+    /// Hides the window along with its linked windows
+    pub fn hide(&self, window: &Window<Wry>) -> Result<(), Error> {
+        self.hide_with_linked(window, true)
+    }
+
+    fn hide_with_linked(&self, window: &Window<Wry>, slide_off: bool) -> Result<(), Error> {
+        self.hide_window(window, slide_off)?;
+        for linked in linked_windows(window)? {
+            self.hide_window(&linked, slide_off)?;
+        }
+        for (docked, _) in docked_windows(window)? {
+            self.hide_window(&docked, slide_off)?;
+        }
+        Ok(())
+    }
+
+    fn hide_window(&self, window: &Window<Wry>, slide_off: bool) -> Result<(), Error> {
+        let _lock = self.window_locks.lock(window.label())?;
+        if !window.is_visible().map_err(|_| Error::FailedToCheckWindowVisibility)? {
+            return Ok(());
+        }
+        let window_config = self.get_window_config(window)?.unwrap_or_default();
+        // Saved before the hide animation moves the window, where it is shown again next time
+        if window_config.should_remember_position() && !animation::is_animating(window) {
+            if let Err(err) = save_window_position(window) {
+                log_warn!("failed to save position of spotlight window {}: {}", window.label(), err);
+            }
+        }
+        let hide_animation = animation::hide_animation_for(&window_config.hide_animation).filter(|_| slide_off);
+        if let Some(hide_animation) = hide_animation {
+            return animation::animate_slide_off(window, &hide_animation, |window| {
+                window.app_handle().state::<SpotlightManager>().hide_immediately(window)
+            });
+        }
+        match animation::animation_for(&window_config.animation) {
+            Some(animation) => animation::animate_hide(window, &animation, |window| {
+                window.app_handle().state::<SpotlightManager>().hide_immediately(window)
+            }),
+            None => self.hide_immediately(window),
+        }
+    }
+
+    fn hide_immediately(&self, window: &Window<Wry>) -> Result<(), Error> {
+        let _lock = self.window_locks.lock(window.label())?;
+        log_debug!("hiding spotlight window {}", window.label());
+        self.idle_timers.disarm(window.label())?;
+        self.idle_fade_timers.disarm(window.label())?;
+        if let Some(previous) = self.previous_focus.take(window.label())? {
+            // Not getting focus back is no reason to fail hiding the window
+            if let Err(err) = restore_previous_focus(window, previous) {
+                log_warn!("failed to restore focus after hiding spotlight window {}: {}", window.label(), err);
+            }
+        }
+        if self.native.hide(window)? {
+            animation::cancel(window)?;
+            emit_spotlight_event(window, SPOTLIGHT_DID_HIDE)?;
+        }
+        Ok(())
+    }
+}
+
+/// Whether the toggle shortcuts of the window are registered right now, those that aren't `global`
+/// only are while the app has focus
+fn shortcuts_active(window: &Window<Wry>, window_config: &WindowConfig) -> bool {
+    let app_handle = window.app_handle();
+    !app_handle.state::<SpotlightManager>().shortcuts_suspended() && (window_config.is_global() || app_has_focus(&app_handle))
+}
+
+/// Registers every toggle shortcut of the window, rolling back the registered ones on failure
+fn register_shortcuts_for_window(window: &Window<Wry>, window_config: &WindowConfig) -> Result<(), Error> {
+    let shortcuts = window_config.all_shortcuts();
+    for (i, shortcut) in shortcuts.iter().enumerate() {
+        if let Err(err) = register_shortcut_for_window(window, shortcut) {
+            if let Err(err) = unregister_shortcuts(window, &shortcuts[..i]) {
+                log_error!("failed to roll back shortcuts of window {}: {}", window.label(), err);
+            }
+            return Err(err);
+        }
+    }
+    Ok(())
+}
+
+fn unregister_shortcuts(window: &Window<Wry>, shortcuts: &[String]) -> Result<(), Error> {
+    let mut shortcut_manager = shortcut_backend(&window.app_handle());
+    for shortcut in shortcuts {
+        shortcut_manager.unregister(shortcut).map_err(Error::unregister_failed(shortcut))?;
+        log_debug!("unregistered shortcut {} of spotlight window {}", shortcut, window.label());
+    }
+    Ok(())
+}
+
+fn unregister_if_registered<B: ShortcutBackend>(backend: &mut B, shortcut: &str) -> Result<(), Error> {
+    if backend.is_registered(shortcut).map_err(Error::query_failed(shortcut))? {
+        backend.unregister(shortcut).map_err(Error::unregister_failed(shortcut))?;
+    }
+    Ok(())
+}
+
+fn register_shortcut_for_window(window: &Window<Wry>, shortcut: &str) -> Result<(), Error> {
+    let mut shortcut_manager = shortcut_backend(&window.app_handle());
+    register_exclusive(&mut shortcut_manager, window.label(), shortcut, toggle_handler(window))
+}
+
+/// Registers a toggle shortcut of a window registered again, replacing the one registered for the
+/// previous window with its label
+fn replace_shortcut_for_window(window: &Window<Wry>, shortcut: &str) -> Result<(), Error> {
+    let mut shortcut_manager = shortcut_backend(&window.app_handle());
+    register_replacing(&mut shortcut_manager, window.label(), shortcut, toggle_handler(window))
+}
+
+fn toggle_handler(window: &Window<Wry>) -> ShortcutHandler {
+    let window = window.to_owned();
+    Box::new(move || {
+        let app_handle = window.app_handle();
+        app_handle.state::<SpotlightManager>().toggle_from_shortcut(&window);
+    })
+}
+
+/// Registers the global close shortcut for as long as the window is a spotlight window
+fn register_close_shortcut(window: &Window<Wry>) -> Result<(), Error> {
+    let app_handle = window.app_handle();
+    let manager = app_handle.state::<SpotlightManager>();
+    if manager.shortcuts_suspended() {
+        return Ok(());
+    }
+    let close_shortcut = match manager.config()?.global_close_shortcut {
+        Some(close_shortcut) => close_shortcut,
+        None => return Ok(()),
+    };
+    manager.close_shortcut_holders.acquire_shortcut(
+        &mut shortcut_backend(&app_handle),
+        window.label(),
+        &close_shortcut,
+        global_close_handler(&app_handle),
+    )
+}
+
+/// Hides all spotlight windows when the global close shortcut is pressed
+fn global_close_handler(app_handle: &AppHandle<Wry>) -> ShortcutHandler {
+    let handle = app_handle.clone();
+    Box::new(move || {
+        let manager = handle.state::<SpotlightManager>();
+        let scope = match manager.config() {
+            Ok(config) => config.close_scope(),
+            Err(err) => return log_error!("failed to read the spotlight config: {}", err),
+        };
+        let labels = match manager.visible_labels(&handle) {
+            Ok(labels) if scope == CloseScope::Frontmost => frontmost_label(manager.app_focus(), &labels).into_iter().collect(),
+            Ok(labels) => labels,
+            Err(err) => {
+                log_error!("failed to list visible spotlight windows: {}", err);
+                vec![]
+            }
+        };
+        if let Err(err) = emit_global_close(&handle, labels.clone()) {
+            log_error!("failed to emit the global close event: {}", err);
+        }
+        // Hiding a hidden window does nothing, so the shortcut only acts while some spotlight
+        // window is visible. Windows are meant to be gone at once, so none slide off screen
+        let result = match scope {
+            CloseScope::All => manager.hide_all_without_sliding(&handle),
+            CloseScope::Frontmost => match labels.first().and_then(|label| handle.get_window(label)) {
+                Some(window) => manager.hide_with_linked(&window, false),
+                None => Ok(()),
+            },
+        };
+        if let Err(err) = result {
+            log_error!("failed to hide spotlight windows: {}", err);
+        }
+    })
+}
+
+/// The focused one of the visible spotlight windows, or the one that had focus last. If none of
+/// them ever had focus, e.g. as they were all shown without taking it, the one registered last
+fn frontmost_label(app_focus: &AppFocus, visible: &[String]) -> Option<String> {
+    app_focus.last_focused(visible).or_else(|| visible.last().cloned())
+}
+
+fn unregister_close_shortcut(window: &Window<Wry>) -> Result<(), Error> {
+    let app_handle = window.app_handle();
+    let manager = app_handle.state::<SpotlightManager>();
+    match manager.config()?.global_close_shortcut {
+        Some(close_shortcut) => manager.close_shortcut_holders.release_shortcut(
+            &mut shortcut_backend(&app_handle),
+            window.label(),
+            &close_shortcut,
+        ),
+        None => Ok(()),
+    }
+}
+
+/// Registers the global toggle shortcut for as long as the window is a spotlight window
+fn register_toggle_all_shortcut(window: &Window<Wry>) -> Result<(), Error> {
+    let app_handle = window.app_handle();
+    let manager = app_handle.state::<SpotlightManager>();
+    if manager.shortcuts_suspended() {
+        return Ok(());
+    }
+    let toggle_shortcut = match manager.config()?.global_toggle_shortcut {
+        Some(toggle_shortcut) => toggle_shortcut,
+        None => return Ok(()),
+    };
+    let handle = app_handle.clone();
+    manager.toggle_shortcut_holders.acquire_shortcut(
+        &mut shortcut_backend(&app_handle),
+        window.label(),
+        &toggle_shortcut,
+        Box::new(move || {
+            if let Err(err) = handle.state::<SpotlightManager>().toggle_all(&handle) {
+                log_error!("failed to toggle spotlight windows: {}", err);
+            }
+        }),
+    )
+}
+
+fn unregister_toggle_all_shortcut(window: &Window<Wry>) -> Result<(), Error> {
+    let app_handle = window.app_handle();
+    let manager = app_handle.state::<SpotlightManager>();
+    match manager.config()?.global_toggle_shortcut {
+        Some(toggle_shortcut) => manager.toggle_shortcut_holders.release_shortcut(
+            &mut shortcut_backend(&app_handle),
+            window.label(),
+            &toggle_shortcut,
+        ),
+        None => Ok(()),
+    }
+}
+
+/// Registers the close shortcut of the given window to hide just that window, while the window is focused
+fn register_window_close_shortcut(window: &Window<Wry>) -> Result<(), Error> {
+    let window = window.to_owned();
+    if window.app_handle().state::<SpotlightManager>().shortcuts_suspended() {
+        return Ok(());
+    }
+    let close_shortcut = match window
+        .app_handle()
+        .state::<SpotlightManager>()
+        .get_window_config(&window)?
+        .and_then(|window_config| window_config.window_close_shortcut())
+    {
+        Some(close_shortcut) => close_shortcut,
+        None => return Ok(()),
+    };
+    let mut shortcut_manager = shortcut_backend(&window.app_handle());
+    if !shortcut_manager.is_registered(&close_shortcut).map_err(Error::query_failed(&close_shortcut))? {
+        log_debug!("registering close shortcut {} of focused spotlight window {}", close_shortcut, window.label());
+        shortcut_manager.register(&close_shortcut, Box::new(move || {
+            let app_handle = window.app_handle();
+            if let Err(err) = app_handle.state::<SpotlightManager>().hide(&window) {
+                log_error!("failed to hide spotlight window {}: {}", window.label(), err);
+            }
+        })).map_err(Error::register_failed(&close_shortcut))?;
+    }
+    Ok(())
+}
+
+fn unregister_window_close_shortcut(window: &Window<Wry>) -> Result<(), Error> {
+    let app_handle = window.app_handle();
+    let manager = app_handle.state::<SpotlightManager>();
+    let close_shortcut = match manager
+        .get_window_config(window)?
+        .and_then(|window_config| window_config.window_close_shortcut())
+    {
+        Some(close_shortcut) => close_shortcut,
+        None => return Ok(()),
+    };
+    // It may be the global close shortcut too, which manages its own registration
+    if manager.config()?.global_close_shortcut.as_deref() == Some(close_shortcut.as_str()) {
+        return Ok(());
+    }
+    let mut shortcut_manager = shortcut_backend(&app_handle);
+    if shortcut_manager.is_registered(&close_shortcut).map_err(Error::query_failed(&close_shortcut))? {
+        shortcut_manager.unregister(&close_shortcut).map_err(Error::unregister_failed(&close_shortcut))?;
+        log_debug!("unregistered close shortcut {} of spotlight window {}", close_shortcut, window.label());
+    }
+    Ok(())
+}
+
+fn handle_focus_state_change(window: &Window<Wry>) {
+    let w = window.to_owned();
+    window.on_window_event(move |event| {
+        // Handlers can't be detached, so this one outlives the registration of the window
+        if !w.app_handle().state::<SpotlightManager>().is_registered(w.label()) {
+            return;
+        }
+        match event {
+            WindowEvent::Focused(false) => {
+                if let Err(err) = unregister_window_close_shortcut(&w) {
+                    log_error!("failed to unregister close shortcut of window {}: {}", w.label(), err);
+                }
+                let app_handle = w.app_handle();
+                let manager = app_handle.state::<SpotlightManager>();
+                let action = manager
+                    .get_window_config(&w)
+                    .ok()
+                    .flatten()
+                    .map_or(BlurAction::Hide, |window_config| match window_config.blur_action() {
+                        // Windows that don't take focus would hide whenever they are clicked away from
+                        _ if !window_config.should_grab_focus() => BlurAction::Nothing,
+                        // Where outside clicks can't be detected, fall back to focus loss
+                        BlurAction::Nothing if !DETECTS_OUTSIDE_CLICKS && window_config.should_hide_on_outside_click() => {
+                            BlurAction::Hide
+                        }
+                        action => action,
+                    });
+                let suspended = manager.is_auto_hide_suspended(w.label()).unwrap_or(false)
+                    || manager.is_cooling_down(&w).unwrap_or(false);
+                log_debug!("spotlight window {} lost focus, action: {:?}, suspended: {}", w.label(), action, suspended);
+                let result = match action {
+                    BlurAction::Hide if !suspended => linked::auto_hide(&w),
+                    BlurAction::Dim(opacity) if !suspended => linked::dim(&w, opacity)
+                        .and_then(|_| emit_spotlight_event(&w, WINDOW_DID_RESIGN_KEY)),
+                    _ => emit_spotlight_event(&w, WINDOW_DID_RESIGN_KEY),
+                };
+                if let Err(err) = result {
+                    log_error!("failed to handle focus loss of spotlight window {}: {}", w.label(), err);
+                }
+            }
+            WindowEvent::Focused(true) => {
+                log_debug!("spotlight window {} got focus", w.label());
+                if let Err(err) = animation::undim(&w) {
+                    log_error!("failed to restore opacity of window {}: {}", w.label(), err);
+                }
+                if let Err(err) = register_window_close_shortcut(&w) {
+                    log_error!("failed to register close shortcut of window {}: {}", w.label(), err);
+                }
+                if let Err(err) = w.app_handle().state::<SpotlightManager>().reset_idle_timer(&w) {
+                    log_error!("failed to reset idle timer of window {}: {}", w.label(), err);
+                }
+            }
+            _ => {}
+        }
+    });
+}
+
+/// Cleans up after the window once it is destroyed
+fn handle_window_destroyed(window: &Window<Wry>) {
+    let w = window.to_owned();
+    window.on_window_event(move |event| {
+        if let WindowEvent::Destroyed = event {
+            let app_handle = w.app_handle();
+            let manager = app_handle.state::<SpotlightManager>();
+            let result = manager
+                .forget_event_handlers(w.label())
+                .and_then(|_| manager.unregister_spotlight_window(&w));
+            if let Err(err) = result {
+                log_error!("failed to clean up destroyed spotlight window {}: {}", w.label(), err);
+            }
+        }
+    });
+}
@@ -0,0 +1,95 @@
+use std::{
+    os::raw::{c_char, c_long},
+    sync::Once,
+};
+use gdk_x11_sys::{
+    gdk_x11_display_get_xdisplay, gdk_x11_get_server_time, gdk_x11_window_get_type, gdk_x11_window_get_xid,
+    GdkX11Display, GdkX11Window,
+};
+use gtk::{
+    gdk,
+    glib::{translate::{FromGlib, ToGlibPtr}, Type},
+    prelude::{ObjectExt, WidgetExt},
+};
+use tauri::{PhysicalPosition, Window, Wry};
+use x11::xlib;
+use super::Error;
+
+/// `_NET_ACTIVE_WINDOW` source indication of pagers, which window managers don't second-guess
+const SOURCE_INDICATION_PAGER: c_long = 2;
+
+static WARN_UNSUPPORTED_BACKEND: Once = Once::new();
+
+/// Raises the window and asks the window manager to activate it through `_NET_ACTIVE_WINDOW`.
+/// GTK objects only live on the main thread, so the request is sent from there.
+pub(crate) fn bring_window_to_front(window: &Window<Wry>) -> Result<(), Error> {
+    let w = window.to_owned();
+    window.run_on_main_thread(move || {
+        if let Err(err) = raise_x11_window(&w) {
+            log_error!("failed to bring spotlight window {} to front: {}", w.label(), err);
+        }
+    })?;
+    Ok(())
+}
+
+/// Returns where the pointer is in physical pixels. GTK objects only live on the main thread, so
+/// it is unknown anywhere else
+pub(crate) fn cursor_position() -> Option<PhysicalPosition<i32>> {
+    if !gtk::is_initialized_main_thread() {
+        return None;
+    }
+    let display = gdk::Display::default()?;
+    let (_, x, y) = display.default_seat()?.pointer()?.position();
+    // GDK counts in logical pixels of the monitor under the pointer
+    let scale_factor = display.monitor_at_point(x, y).map_or(1, |monitor| monitor.scale_factor());
+    Some(PhysicalPosition { x: x * scale_factor, y: y * scale_factor })
+}
+
+fn raise_x11_window(window: &Window<Wry>) -> Result<(), Error> {
+    let gtk_window = window.gtk_window().map_err(|_| Error::FailedToGetWindowHandle)?;
+    let gdk_window = match gtk_window.window() {
+        Some(gdk_window) => gdk_window,
+        None => return Ok(()),
+    };
+    let x11_window_type = unsafe { Type::from_glib(gdk_x11_window_get_type()) };
+    if !gdk_window.type_().is_a(x11_window_type) {
+        // Wayland has no way to raise a window programmatically, showing and focusing it is all
+        // that can be done there
+        WARN_UNSUPPORTED_BACKEND.call_once(|| {
+            log_warn!("spotlight windows can't be brought to front outside of X11, e.g. on Wayland");
+        });
+        return Ok(());
+    }
+    let display = gdk_window.display();
+    let gdk_window_ptr: *mut gdk::ffi::GdkWindow = gdk_window.to_glib_none().0;
+    let gdk_display_ptr: *mut gdk::ffi::GdkDisplay = display.to_glib_none().0;
+    unsafe {
+        let xdisplay = gdk_x11_display_get_xdisplay(gdk_display_ptr as *mut GdkX11Display);
+        let xid = gdk_x11_window_get_xid(gdk_window_ptr as *mut GdkX11Window);
+        let timestamp = gdk_x11_get_server_time(gdk_window_ptr as *mut GdkX11Window);
+        let atom = xlib::XInternAtom(xdisplay, b"_NET_ACTIVE_WINDOW\0".as_ptr() as *const c_char, xlib::False);
+        let mut message = xlib::XClientMessageEvent {
+            type_: xlib::ClientMessage,
+            serial: 0,
+            send_event: xlib::True,
+            display: xdisplay,
+            window: xid,
+            message_type: atom,
+            format: 32,
+            data: xlib::ClientMessageData::new(),
+        };
+        message.data.set_long(0, SOURCE_INDICATION_PAGER);
+        message.data.set_long(1, timestamp as c_long);
+        let mut event = xlib::XEvent::from(message);
+        xlib::XSendEvent(
+            xdisplay,
+            xlib::XDefaultRootWindow(xdisplay),
+            xlib::False,
+            xlib::SubstructureRedirectMask | xlib::SubstructureNotifyMask,
+            &mut event,
+        );
+        xlib::XRaiseWindow(xdisplay, xid);
+        xlib::XFlush(xdisplay);
+    }
+    Ok(())
+}
@@ -1,4 +1,9 @@
+mod native;
+mod outside_click;
 mod panel;
-mod spotlight;
 
-pub use spotlight::SpotlightManager;
+pub(crate) use native::{
+    capture_previous_focus, is_laid_out, position_window_by_preset, position_window_on_its_monitor,
+    position_window_on_monitor, preset_target_monitor, restore_previous_focus, target_monitor_at,
+    target_monitor_of_window, NativeWindows, DETECTS_OUTSIDE_CLICKS,
+};
@@ -0,0 +1,481 @@
+use std::{collections::HashMap, sync::{Mutex, RwLock}};
+use cocoa::{
+    appkit::{CGFloat, NSApp, NSApplication, NSWindow, NSWindowCollectionBehavior},
+    base::{id, nil, BOOL, NO, YES},
+    foundation::{NSInteger, NSPoint, NSRect},
+};
+use objc_id::ShareId;
+use objc::{class, msg_send, sel, sel_impl};
+use tauri::{PhysicalPosition, PhysicalSize, Window, Wry};
+use super::outside_click::OutsideClicks;
+use super::panel::{create_spotlight_panel, RawNSPanel};
+use crate::{PositionPreset, WindowConfig, WindowPosition};
+use crate::Error;
+use crate::animation;
+use crate::state::{monitor_at, TargetMonitor};
+use crate::position::{center_in_range, clamp_in_range, offset_from_top, rescale};
+
+#[link(name = "Foundation", kind = "framework")]
+extern "C" {
+    pub fn NSMouseInRect(aPoint: NSPoint, aRect: NSRect, flipped: BOOL) -> BOOL;
+}
+
+/// Whether clicks outside of a window can be told apart from it just losing focus
+pub(crate) const DETECTS_OUTSIDE_CLICKS: bool = true;
+
+#[derive(Default, Debug)]
+pub(crate) struct NativeWindows {
+    /// Panels of the registered windows that are converted to one, by label
+    panels: RwLock<HashMap<String, Mutex<ShareId<RawNSPanel>>>>,
+    pub(super) outside_clicks: OutsideClicks,
+}
+
+impl NativeWindows {
+    /// Applies the parts of the config that depend on the platform to a window being registered,
+    /// converting it to a panel unless it opted out
+    pub(crate) fn set_up(&self, window: &Window<Wry>, window_config: &WindowConfig) -> Result<(), Error> {
+        if window_config.should_use_macos_panel() {
+            let panel = create_spotlight_panel(window, window_config.should_auto_hide());
+            let mut panels = self.panels.write().map_err(|_| Error::RwLock(String::from("failed to write registered panels")))?;
+            panels.insert(window.label().to_string(), Mutex::new(panel));
+        }
+        set_window_level(window, window_config)?;
+        // After the panel conversion, which sets the collection behaviour of its own
+        set_join_all_spaces(window, window_config.should_join_all_spaces())?;
+        if window_config.should_skip_taskbar() {
+            hide_from_window_cycle(window)?;
+        }
+        if window_config.should_hide_on_outside_click() {
+            self.outside_clicks.watch(window)?;
+        }
+        Ok(())
+    }
+
+    /// Lets go of a window that is no longer registered
+    pub(crate) fn forget(&self, label: &str) -> Result<(), Error> {
+        self.outside_clicks.unwatch(label)?;
+        let mut panels = self.panels.write().map_err(|_| Error::RwLock(String::from("failed to write registered panels")))?;
+        if let Some(panel) = panels.remove(label) {
+            // The panel is Tauri's NSWindow, which was never retained on our side
+            std::mem::forget(panel);
+        }
+        Ok(())
+    }
+
+    /// Panels without a previous delegate hide themselves on focus loss
+    pub(crate) fn set_auto_hide(&self, label: &str, auto_hide: bool) -> Result<(), Error> {
+        let panels = self.panels.read().map_err(|_| Error::RwLock(String::from("failed to read registered panels")))?;
+        if let Some(panel) = panels.get(label) {
+            let panel = panel.lock().map_err(|_| Error::Mutex(String::from("failed to lock panel")))?;
+            panel.set_auto_hide(auto_hide);
+        }
+        Ok(())
+    }
+
+    /// Shows a hidden window, or one that is fading out, and brings a visible panel to the front.
+    /// Returns whether the window was hidden
+    pub(crate) fn show(&self, window: &Window<Wry>, window_config: &WindowConfig, hiding: bool, grab_focus: bool) -> Result<bool, Error> {
+        if window_config.should_stay_on_top() || window_config.macos_window_level.is_some() {
+            set_window_level(window, window_config)?;
+        }
+        let panels = self.panels.read().map_err(|_| Error::RwLock(String::from("failed to read registered panels")))?;
+        if let Some(panel) = panels.get(window.label()) {
+            let panel = panel.lock().map_err(|_| Error::Mutex(String::from("failed to lock panel")))?;
+            let was_visible = panel.is_visible();
+            if !was_visible || hiding {
+                animation::start_show(window, &window_config.animation)?;
+            }
+            if !grab_focus {
+                panel.order_front_regardless();
+            } else {
+                if window_config.should_activate_macos_app() {
+                    activate_app();
+                }
+                panel.show();
+            }
+            return Ok(!was_visible);
+        }
+        let was_visible = window.is_visible().map_err(|_| Error::FailedToCheckWindowVisibility)?;
+        if was_visible && !hiding {
+            return Ok(false);
+        }
+        animation::start_show(window, &window_config.animation)?;
+        if !grab_focus {
+            // Showing the window through Tauri makes it key
+            let handle: id = window.ns_window().map_err(|_| Error::FailedToGetNSWindow)? as _;
+            let _: () = unsafe { msg_send![handle, orderFrontRegardless] };
+        } else if window_config.should_activate_macos_app() {
+            window.show().map_err(|_| Error::FailedToShowWindow)?;
+            // Focusing a window through Tauri activates the app as well
+            if let Err(err) = window.set_focus().map_err(|_| Error::FailedToFocusWindow) {
+                log_warn!("spotlight window {} is shown but not focused: {}", window.label(), err);
+            }
+        } else {
+            window.show().map_err(|_| Error::FailedToShowWindow)?;
+            let handle: id = window.ns_window().map_err(|_| Error::FailedToGetNSWindow)? as _;
+            let _: () = unsafe { msg_send![handle, orderFrontRegardless] };
+            let _: () = unsafe { msg_send![handle, makeKeyWindow] };
+        }
+        Ok(!was_visible)
+    }
+
+    /// Hides a visible window. Returns whether it was visible
+    pub(crate) fn hide(&self, window: &Window<Wry>) -> Result<bool, Error> {
+        let panels = self.panels.read().map_err(|_| Error::RwLock(String::from("failed to read registered panels")))?;
+        if let Some(panel) = panels.get(window.label()) {
+            let panel = panel.lock().map_err(|_| Error::Mutex(String::from("failed to lock panel")))?;
+            let was_visible = panel.is_visible();
+            panel.order_out(None);
+            return Ok(was_visible);
+        }
+        if !window.is_visible().map_err(|_| Error::FailedToCheckWindowVisibility)? {
+            return Ok(false);
+        }
+        window.hide().map_err(|_| Error::FailedToHideWindow)?;
+        Ok(true)
+    }
+}
+
+/// Makes the app the active one, which switches the menu bar over to it
+fn activate_app() {
+    unsafe { NSApp().activateIgnoringOtherApps_(YES) };
+}
+
+/// Returns the process id of the frontmost app unless it is this one, to give focus back to later
+pub(crate) fn capture_previous_focus(_window: &Window<Wry>) -> Result<Option<isize>, Error> {
+    let pid: i32 = unsafe {
+        let workspace: id = msg_send![class!(NSWorkspace), sharedWorkspace];
+        let app: id = msg_send![workspace, frontmostApplication];
+        if app == nil {
+            return Ok(None);
+        }
+        msg_send![app, processIdentifier]
+    };
+    Ok((pid as u32 != std::process::id()).then(|| pid as isize))
+}
+
+/// Activates the previously frontmost app again, unless it quit in the meantime or the user already
+/// switched away from this app
+pub(crate) fn restore_previous_focus(_window: &Window<Wry>, previous: isize) -> Result<(), Error> {
+    unsafe {
+        let active: BOOL = msg_send![NSApp(), isActive];
+        if active != YES {
+            return Ok(());
+        }
+        let app: id = msg_send![class!(NSRunningApplication), runningApplicationWithProcessIdentifier: previous as i32];
+        if app != nil {
+            // NSApplicationActivateIgnoringOtherApps
+            let _: BOOL = msg_send![app, activateWithOptions: 1u64 << 1];
+        }
+    }
+    Ok(())
+}
+
+/// Applies `macos_window_level`, or the floating level of always on top windows. Panels already sit
+/// above the menu bar, so they are left alone unless a level is configured. The level is only set
+/// if it changed, e.g. after macOS reset it while the window was hidden
+fn set_window_level(window: &Window<Wry>, window_config: &WindowConfig) -> Result<(), Error> {
+    if let Some(level) = window_config.macos_window_level {
+        let handle: id = window.ns_window().map_err(|_| Error::FailedToGetNSWindow)? as _;
+        let level = NSInteger::from(level.level());
+        unsafe {
+            if handle.level() != level {
+                handle.setLevel_(level);
+            }
+        }
+    } else if window_config.should_stay_on_top() && !window_config.should_use_macos_panel() {
+        window.set_always_on_top(true)?;
+    }
+    Ok(())
+}
+
+/// Makes the window show on the active space, or stay on the space it was last shown on
+fn set_join_all_spaces(window: &Window<Wry>, join_all_spaces: bool) -> Result<(), Error> {
+    let handle: id = window.ns_window().map_err(|_| Error::FailedToGetNSWindow)? as _;
+    unsafe {
+        let behaviour = handle.collectionBehavior();
+        // Joining all spaces and moving to the active one are mutually exclusive
+        let behaviour = if join_all_spaces {
+            (behaviour | NSWindowCollectionBehavior::NSWindowCollectionBehaviorCanJoinAllSpaces)
+                & !NSWindowCollectionBehavior::NSWindowCollectionBehaviorMoveToActiveSpace
+        } else {
+            behaviour & !NSWindowCollectionBehavior::NSWindowCollectionBehaviorCanJoinAllSpaces
+        };
+        handle.setCollectionBehavior_(behaviour);
+    }
+    Ok(())
+}
+
+/// Leaves the window out of the Cmd+` window cycle. Its app still shows in the dock and Cmd+Tab,
+/// which only the activation policy of the whole app can change
+fn hide_from_window_cycle(window: &Window<Wry>) -> Result<(), Error> {
+    let handle: id = window.ns_window().map_err(|_| Error::FailedToGetNSWindow)? as _;
+    unsafe {
+        let behaviour = handle.collectionBehavior() | NSWindowCollectionBehavior::NSWindowCollectionBehaviorIgnoresCycle;
+        handle.setCollectionBehavior_(behaviour);
+    }
+    Ok(())
+}
+
+#[macro_export]
+macro_rules! nsstring_to_string {
+    ($ns_string:expr) => {{
+        use objc::{sel, sel_impl};
+        let utf8: id = objc::msg_send![$ns_string, UTF8String];
+        let string = if !utf8.is_null() {
+            Some({
+                std::ffi::CStr::from_ptr(utf8 as *const std::os::raw::c_char)
+                    .to_string_lossy()
+                    .into_owned()
+            })
+        } else {
+            None
+        };
+        string
+    }};
+}
+
+/// Where `show` lays the window out by its position preset
+enum PresetMonitor {
+    /// In the work area of the screen
+    Screen(Monitor),
+    /// On the whole monitor
+    Whole(tauri::Monitor),
+}
+
+/// Returns the monitor `show` lays the window out on by its position preset. A window centered on
+/// its own monitor goes to the primary monitor until it was shown, as its position means nothing
+/// before
+fn preset_monitor(window: &Window<Wry>, window_config: &WindowConfig, shown_before: bool) -> Result<Option<PresetMonitor>, Error> {
+    match window_config.position.unwrap_or(WindowPosition::CenterActiveMonitor) {
+        WindowPosition::CenterActiveMonitor | WindowPosition::AtCursor { .. } => {
+            Ok(get_monitor_with_cursor().map(PresetMonitor::Screen))
+        }
+        WindowPosition::CenterOwnMonitor if shown_before => Ok(monitor_of_window(window)?.map(PresetMonitor::Screen)),
+        WindowPosition::CenterOwnMonitor => match window.primary_monitor().map_err(Error::MonitorQueryFailed)? {
+            Some(monitor) => Ok(Some(PresetMonitor::Whole(monitor))),
+            None => Ok(monitor_of_window(window)?.map(PresetMonitor::Screen)),
+        },
+    }
+}
+
+/// Places the window at the cursor or as laid out by its position preset
+pub(crate) fn position_window_by_preset(window: &Window<Wry>, window_config: &WindowConfig, shown_before: bool) -> Result<(), Error> {
+    if position_window_at_cursor(window, window_config)? {
+        return Ok(());
+    }
+    match preset_monitor(window, window_config, shown_before)? {
+        Some(PresetMonitor::Screen(monitor)) => position_window_in_work_area(window, &monitor, window_config.placement()),
+        Some(PresetMonitor::Whole(monitor)) => position_window_on_monitor(window, &monitor, window_config.placement()),
+        None => Ok(()),
+    }
+}
+
+/// Whether `show` lays the window out by its position preset, which a window without a position
+/// is too, on the monitor with the cursor
+pub(crate) fn is_laid_out(_window_config: &WindowConfig) -> bool {
+    true
+}
+
+/// Describes the whole monitor containing the point
+pub(crate) fn target_monitor_at(window: &Window<Wry>, point: PhysicalPosition<i32>) -> Result<Option<TargetMonitor>, Error> {
+    Ok(monitor_at(window, point)?.map(|monitor| TargetMonitor::new(&monitor, *monitor.position(), *monitor.size())))
+}
+
+/// Describes the monitor `show` lays the window out on by its position preset
+pub(crate) fn preset_target_monitor(window: &Window<Wry>, window_config: &WindowConfig, shown_before: bool) -> Result<Option<TargetMonitor>, Error> {
+    match preset_monitor(window, window_config, shown_before)? {
+        Some(PresetMonitor::Screen(monitor)) => target_monitor_of_screen(window, &monitor),
+        Some(PresetMonitor::Whole(monitor)) => Ok(Some(TargetMonitor::new(&monitor, *monitor.position(), *monitor.size()))),
+        None => Ok(None),
+    }
+}
+
+/// Describes the monitor the window is on
+pub(crate) fn target_monitor_of_window(window: &Window<Wry>) -> Result<Option<TargetMonitor>, Error> {
+    match monitor_of_window(window)? {
+        Some(monitor) => target_monitor_of_screen(window, &monitor),
+        None => Ok(None),
+    }
+}
+
+/// Positions a given window in the work area of the monitor it is on, as laid out by the preset
+pub(crate) fn position_window_on_its_monitor(window: &Window<Wry>, preset: PositionPreset) -> Result<(), Error> {
+    match monitor_of_window(window)? {
+        Some(monitor) => position_window_in_work_area(window, &monitor, preset),
+        None => Ok(()),
+    }
+}
+
+/// Returns the screen the window is on, `None` if it is off screen
+fn monitor_of_window(window: &Window<Wry>) -> Result<Option<Monitor>, Error> {
+    let handle: id = window.ns_window().map_err(|_| Error::FailedToGetNSWindow)? as _;
+    let screen: id = unsafe { msg_send![handle, screen] };
+    Ok((screen != nil).then(|| monitor_of_screen(screen)))
+}
+
+/// Describes the monitor of the screen in the coordinates Tauri uses, matching them by their left
+/// edge and size since only Tauri's origin is the top-left corner of the primary monitor
+fn target_monitor_of_screen(window: &Window<Wry>, monitor: &Monitor) -> Result<Option<TargetMonitor>, Error> {
+    let matching = window
+        .available_monitors().map_err(Error::MonitorQueryFailed)?
+        .into_iter()
+        .find(|m| m.position().x == monitor.position.x && *m.size() == monitor.size);
+    Ok(matching.map(|m| {
+        // Cocoa's origin is the bottom-left corner, so the work area is placed by how far its top
+        // edge is from the top of the screen
+        let top_inset = (monitor.position.y + monitor.size.height as i32)
+            - (monitor.work_area_position.y + monitor.work_area_size.height as i32);
+        let work_area_position = PhysicalPosition { x: monitor.work_area_position.x, y: m.position().y + top_inset };
+        TargetMonitor::new(&m, work_area_position, monitor.work_area_size)
+    }))
+}
+
+fn position_window_in_work_area(window: &Window<Wry>, monitor: &Monitor, preset: PositionPreset) -> Result<(), Error> {
+    let work_area_size = monitor.work_area_size.to_logical::<f64>(monitor.scale_factor);
+    let work_area_pos = monitor.work_area_position.to_logical::<f64>(monitor.scale_factor);
+    let handle: id = window.ns_window().map_err(|_| Error::FailedToGetNSWindow)? as _;
+    let win_frame: NSRect = unsafe { handle.frame() };
+    // Cocoa's origin is the bottom-left corner, so windows taller than the work area are
+    // pinned to its top edge instead of its bottom edge
+    let work_area_top = work_area_pos.y + work_area_size.height;
+    let offset = offset_from_top(preset, work_area_size.height, win_frame.size.height);
+    let rect = NSRect {
+        origin: NSPoint {
+            x: center_in_range(work_area_pos.x, work_area_size.width, win_frame.size.width),
+            y: work_area_top - offset - win_frame.size.height,
+        },
+        size: win_frame.size,
+    };
+    let _: () = unsafe { msg_send![handle, setFrame: rect display: YES] };
+    Ok(())
+}
+
+/// Puts the top-left corner of a window shown at the cursor there, offset by logical pixels and
+/// kept in the work area of the screen under the cursor. Returns `false` for other windows, or if
+/// the cursor isn't on any screen, those are laid out by their position preset
+fn position_window_at_cursor(window: &Window<Wry>, window_config: &WindowConfig) -> Result<bool, Error> {
+    let (offset_x, offset_y) = match window_config.position {
+        Some(WindowPosition::AtCursor { offset_x, offset_y }) => (offset_x as f64, offset_y as f64),
+        _ => return Ok(false),
+    };
+    let handle: id = window.ns_window().map_err(|_| Error::FailedToGetNSWindow)? as _;
+    objc::rc::autoreleasepool(|| {
+        let mouse_location: NSPoint = unsafe { msg_send![class!(NSEvent), mouseLocation] };
+        let screen = match screen_at(mouse_location) {
+            Some(screen) => screen,
+            None => return Ok(false),
+        };
+        let visible_frame: NSRect = unsafe { msg_send![screen, visibleFrame] };
+        let win_frame: NSRect = unsafe { handle.frame() };
+        // Cocoa's origin is the bottom-left corner, so the window is placed by how far its top edge
+        // is below the top of the work area
+        let work_area_top = visible_frame.origin.y + visible_frame.size.height;
+        let top = clamp_in_range(
+            0.0,
+            visible_frame.size.height,
+            win_frame.size.height,
+            work_area_top - (mouse_location.y - offset_y),
+        );
+        let rect = NSRect {
+            origin: NSPoint {
+                x: clamp_in_range(
+                    visible_frame.origin.x,
+                    visible_frame.size.width,
+                    win_frame.size.width,
+                    mouse_location.x + offset_x,
+                ),
+                y: work_area_top - top - win_frame.size.height,
+            },
+            size: win_frame.size,
+        };
+        let _: () = unsafe { msg_send![handle, setFrame: rect display: YES] };
+        Ok(true)
+    })
+}
+
+/// Positions a given window on the monitor, as laid out by the preset
+pub(crate) fn position_window_on_monitor(window: &Window<Wry>, monitor: &tauri::Monitor, preset: PositionPreset) -> Result<(), Error> {
+    // The window is rescaled by the system once it lands on a monitor with another DPI
+    let window_size = window.outer_size().map_err(Error::position_failed("failed to get window size"))?;
+    let (width, height) = rescale(
+        window_size.width as f64,
+        window_size.height as f64,
+        window.scale_factor().map_err(Error::position_failed("failed to get scale factor"))?,
+        monitor.scale_factor(),
+    );
+    let (position, size) = (monitor.position(), monitor.size());
+    window.set_position(PhysicalPosition {
+        x: center_in_range(position.x as f64, size.width as f64, width) as i32,
+        y: (position.y as f64 + offset_from_top(preset, size.height as f64, height)) as i32,
+    })?;
+    Ok(())
+}
+
+struct Monitor {
+    #[allow(dead_code)]
+    pub name: Option<String>,
+    pub size: PhysicalSize<u32>,
+    pub position: PhysicalPosition<i32>,
+    pub work_area_size: PhysicalSize<u32>,
+    pub work_area_position: PhysicalPosition<i32>,
+    pub scale_factor: f64,
+}
+
+/// Returns the Monitor with cursor
+fn get_monitor_with_cursor() -> Option<Monitor> {
+    objc::rc::autoreleasepool(|| {
+        let mouse_location: NSPoint = unsafe { msg_send![class!(NSEvent), mouseLocation] };
+        screen_at(mouse_location).map(monitor_of_screen)
+    })
+}
+
+/// Returns the screen containing the point, in Cocoa's coordinates
+fn screen_at(location: NSPoint) -> Option<id> {
+    let screens: id = unsafe { msg_send![class!(NSScreen), screens] };
+    let screens_iter: id = unsafe { msg_send![screens, objectEnumerator] };
+    loop {
+        let next_screen: id = unsafe { msg_send![screens_iter, nextObject] };
+        if next_screen == nil {
+            return None;
+        }
+
+        let frame: NSRect = unsafe { msg_send![next_screen, frame] };
+        let is_location_in_screen_frame: BOOL = unsafe { NSMouseInRect(location, frame, NO) };
+        if is_location_in_screen_frame == YES {
+            return Some(next_screen);
+        }
+    }
+}
+
+fn monitor_of_screen(screen: id) -> Monitor {
+    objc::rc::autoreleasepool(|| {
+        let frame: NSRect = unsafe { msg_send![screen, frame] };
+        let name: id = unsafe { msg_send![screen, localizedName] };
+        let screen_name = unsafe { nsstring_to_string!(name) };
+        let scale_factor: CGFloat = unsafe { msg_send![screen, backingScaleFactor] };
+        let scale_factor: f64 = scale_factor;
+        let visible_frame: NSRect = unsafe { msg_send![screen, visibleFrame] };
+
+        Monitor {
+            name: screen_name,
+            position: PhysicalPosition {
+                x: (frame.origin.x * scale_factor) as i32,
+                y: (frame.origin.y * scale_factor) as i32,
+            },
+            size: PhysicalSize {
+                width: (frame.size.width * scale_factor) as u32,
+                height: (frame.size.height * scale_factor) as u32,
+            },
+            work_area_position: PhysicalPosition {
+                x: (visible_frame.origin.x * scale_factor) as i32,
+                y: (visible_frame.origin.y * scale_factor) as i32,
+            },
+            work_area_size: PhysicalSize {
+                width: (visible_frame.size.width * scale_factor) as u32,
+                height: (visible_frame.size.height * scale_factor) as u32,
+            },
+            scale_factor,
+        }
+    })
+}
@@ -0,0 +1,78 @@
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Mutex,
+};
+use block::ConcreteBlock;
+use cocoa::base::id;
+use objc::{class, msg_send, sel, sel_impl};
+use tauri::{AppHandle, Manager, Window, Wry};
+use crate::spotlight::SpotlightManager;
+use crate::Error;
+
+const NS_EVENT_MASK_LEFT_MOUSE_DOWN: u64 = 1 << 1;
+const NS_EVENT_MASK_RIGHT_MOUSE_DOWN: u64 = 1 << 3;
+const NS_EVENT_MASK_OTHER_MOUSE_DOWN: u64 = 1 << 25;
+
+/// The windows hiding on outside clicks, watched by a single global event monitor
+#[derive(Default, Debug)]
+pub(super) struct OutsideClicks {
+    windows: Mutex<Vec<Window<Wry>>>,
+    monitor_installed: AtomicBool,
+}
+
+impl OutsideClicks {
+    /// Hides the given window when a mouse button is pressed outside of it.
+    ///
+    /// A global event monitor only receives events delivered to other applications, so clicks on
+    /// other windows of this app don't count as outside clicks.
+    pub(super) fn watch(&self, window: &Window<Wry>) -> Result<(), Error> {
+        let mut windows = self
+            .windows
+            .lock()
+            .map_err(|_| Error::Mutex(String::from("failed to lock watched windows")))?;
+        if !windows.iter().any(|watched| watched.label() == window.label()) {
+            windows.push(window.clone());
+        }
+        std::mem::drop(windows);
+        if !self.monitor_installed.swap(true, Ordering::SeqCst) {
+            let app_handle = window.app_handle();
+            window.run_on_main_thread(move || install_monitor(app_handle))?;
+        }
+        Ok(())
+    }
+
+    /// Stops hiding the window with the given label on outside clicks
+    pub(super) fn unwatch(&self, label: &str) -> Result<(), Error> {
+        let mut windows = self
+            .windows
+            .lock()
+            .map_err(|_| Error::Mutex(String::from("failed to lock watched windows")))?;
+        windows.retain(|watched| watched.label() != label);
+        Ok(())
+    }
+}
+
+fn install_monitor(app_handle: AppHandle<Wry>) {
+    let handler = ConcreteBlock::new(move |_event: id| {
+        let manager = app_handle.state::<SpotlightManager>();
+        let windows = match manager.native_windows().outside_clicks.windows.lock() {
+            Ok(windows) => windows.clone(),
+            Err(_) => return,
+        };
+        for window in windows {
+            if manager.is_auto_hide_suspended(window.label()).unwrap_or(false)
+                || manager.is_within_min_visible(&window).unwrap_or(false)
+            {
+                continue;
+            }
+            if let Err(err) = manager.hide(&window) {
+                log_error!("failed to hide spotlight window {}: {}", window.label(), err);
+            }
+        }
+    })
+    .copy();
+    let mask = NS_EVENT_MASK_LEFT_MOUSE_DOWN | NS_EVENT_MASK_RIGHT_MOUSE_DOWN | NS_EVENT_MASK_OTHER_MOUSE_DOWN;
+    let _: id = unsafe { msg_send![class!(NSEvent), addGlobalMonitorForEventsMatchingMask: mask handler: &*handler] };
+    // The monitor lives as long as the app, so the handler is never released
+    std::mem::forget(handler);
+}
@@ -4,7 +4,7 @@ use bitflags::bitflags;
 use objc_id::{Id, ShareId};
 use cocoa::{
     appkit::{NSMainMenuWindowLevel, NSView, NSViewHeightSizable, NSViewWidthSizable, NSWindowCollectionBehavior},
-    base::{id, nil, BOOL, YES}, foundation::NSRect,
+    base::{id, nil, BOOL, NO, YES}, foundation::NSRect,
 };
 use objc::{
     class,
@@ -136,6 +136,10 @@ impl RawNSPanel {
         let _: () = unsafe { msg_send![self, setStyleMask: style_mask] };
     }
 
+    fn delegate(&self) -> id {
+        unsafe { msg_send![self, delegate] }
+    }
+
     pub(crate) fn set_collection_behaviour(&self, behaviour: NSWindowCollectionBehavior) {
         let _: () = unsafe { msg_send![self, setCollectionBehavior: behaviour] };
     }
@@ -187,12 +191,28 @@ impl RawNSPanelDelegate {
 
         unsafe {
             cls.add_ivar::<id>("panel");
+            cls.add_ivar::<id>("previousDelegate");
 
             cls.add_method(
                 sel!(setPanel:),
                 Self::set_panel as extern "C" fn(&mut Object, Sel, id),
             );
 
+            cls.add_method(
+                sel!(setPreviousDelegate:),
+                Self::set_previous_delegate as extern "C" fn(&mut Object, Sel, id),
+            );
+
+            cls.add_method(
+                sel!(respondsToSelector:),
+                Self::responds_to_selector as extern "C" fn(&Object, Sel, Sel) -> BOOL,
+            );
+
+            cls.add_method(
+                sel!(forwardingTargetForSelector:),
+                Self::forwarding_target_for_selector as extern "C" fn(&Object, Sel, Sel) -> id,
+            );
+
             cls.add_method(
                 sel!(windowDidBecomeKey:),
                 Self::window_did_become_key as extern "C" fn(&Object, Sel, id),
@@ -211,10 +231,51 @@ impl RawNSPanelDelegate {
         unsafe { this.set_ivar("panel", panel) };
     }
 
-    extern "C" fn window_did_become_key(_: &Object, _: Sel, _: id) {}
+    extern "C" fn set_previous_delegate(this: &mut Object, _: Sel, delegate: id) {
+        unsafe { this.set_ivar("previousDelegate", delegate) };
+    }
 
-    /// Hide panel when it's no longer the key window and auto hide is enabled
-    extern "C" fn window_did_resign_key(this: &Object, _: Sel, _: id) {
+    /// Claims the delegate methods of the previous delegate too, so that they get forwarded
+    extern "C" fn responds_to_selector(this: &Object, _: Sel, selector: Sel) -> BOOL {
+        if this.class().instance_method(selector).is_some() {
+            return YES;
+        }
+        let previous: id = unsafe { *this.get_ivar("previousDelegate") };
+        if previous == nil {
+            return NO;
+        }
+        unsafe { msg_send![previous, respondsToSelector: selector] }
+    }
+
+    /// Forwards the delegate methods this class doesn't implement, e.g. moves and resizes
+    extern "C" fn forwarding_target_for_selector(this: &Object, _: Sel, _: Sel) -> id {
+        unsafe { *this.get_ivar("previousDelegate") }
+    }
+
+    /// Forwards the notification to the previous delegate, returns `false` if there is none
+    fn forward_to_previous_delegate(this: &Object, selector: Sel, notification: id) -> bool {
+        let previous: id = unsafe { *this.get_ivar("previousDelegate") };
+        if previous == nil {
+            return false;
+        }
+        let responds: BOOL = unsafe { msg_send![previous, respondsToSelector: selector] };
+        if responds == YES {
+            let _: () = unsafe { msg_send![previous, performSelector: selector withObject: notification] };
+        }
+        true
+    }
+
+    /// Lets Tauri know the panel got focused
+    extern "C" fn window_did_become_key(this: &Object, _: Sel, notification: id) {
+        Self::forward_to_previous_delegate(this, sel!(windowDidBecomeKey:), notification);
+    }
+
+    /// Lets Tauri know the panel lost focus, which hides it if auto hide is enabled. Without a
+    /// previous delegate to report to, the panel hides itself instead
+    extern "C" fn window_did_resign_key(this: &Object, _: Sel, notification: id) {
+        if Self::forward_to_previous_delegate(this, sel!(windowDidResignKey:), notification) {
+            return;
+        }
         let panel: id = unsafe { *this.get_ivar("panel") };
         let auto_hide: BOOL = unsafe { msg_send![panel, autoHide] };
 
@@ -236,9 +297,13 @@ impl RawNSPanelDelegate {
     pub fn set_panel_(&self, panel: ShareId<RawNSPanel>) {
         let _: () = unsafe { msg_send![self, setPanel: panel] };
     }
+
+    pub fn set_previous_delegate_(&self, delegate: id) {
+        let _: () = unsafe { msg_send![self, setPreviousDelegate: delegate] };
+    }
 }
 
-pub(crate) fn create_spotlight_panel(window: &Window<Wry>) -> ShareId<RawNSPanel> {
+pub(crate) fn create_spotlight_panel(window: &Window<Wry>, auto_hide: bool) -> ShareId<RawNSPanel> {
     // Convert NSWindow Object to NSPanel
     let handle: id = window.ns_window().unwrap() as _;
     let panel = RawNSPanel::from(handle);
@@ -248,21 +313,23 @@ pub(crate) fn create_spotlight_panel(window: &Window<Wry>) -> ShareId<RawNSPanel
     panel.set_level(NSMainMenuWindowLevel + 1);
 
     // Set panel to auto hide when it resigns key
-    panel.set_auto_hide(true);
+    panel.set_auto_hide(auto_hide);
 
     // Ensure that the panel can display over the top of fullscreen apps
     panel.set_collection_behaviour(
         NSWindowCollectionBehavior::NSWindowCollectionBehaviorTransient
-            | NSWindowCollectionBehavior::NSWindowCollectionBehaviorMoveToActiveSpace
+            | NSWindowCollectionBehavior::NSWindowCollectionBehaviorCanJoinAllSpaces
             | NSWindowCollectionBehavior::NSWindowCollectionBehaviorFullScreenAuxiliary,
     );
 
     // Ensures panel does not activate
     panel.set_style_mask(NSWindowStyleMaskNonActivatingPanel);
 
-    // Setup delegate for an NSPanel to listen for window resign key and hide the panel
+    // Setup delegate for an NSPanel to listen for window resign key, it keeps forwarding the
+    // delegate methods to Tauri's delegate so that focus and move events still reach the app
     let delegate = RawNSPanelDelegate::new();
     delegate.set_panel_(panel.clone());
+    delegate.set_previous_delegate_(panel.delegate());
     panel.set_delegate(Some(delegate));
 
     // On older macOS i.e on (12.3), hover detection is not working, see https://github.com/ahkohd/tauri-macos-spotlight-example/issues/14
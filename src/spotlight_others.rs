@@ -1,140 +1,619 @@
-use std::sync::Mutex;
-use tauri::{
-    GlobalShortcutManager, Manager, Window, WindowEvent, Wry,
-};
-use super::{PluginConfig, WindowConfig};
-use super::Error;
+use tauri::{Monitor, PhysicalPosition, PhysicalSize, Window, Wry};
+use crate::{PositionPreset, WindowConfig, WindowPosition};
+use crate::Error;
+use crate::animation;
+use crate::state::{monitor_at, TargetMonitor};
+use crate::position::{center_in_range, clamp_in_range, offset_from_top, rescale};
 
+#[cfg(target_os = "linux")]
+#[path = "spotlight_linux.rs"]
+mod linux;
+#[cfg(target_os = "linux")]
+use linux::bring_window_to_front;
+
+/// Whether clicks outside of a window can be told apart from it just losing focus
+pub(crate) const DETECTS_OUTSIDE_CLICKS: bool = cfg!(target_os = "windows");
+
+/// Only the watching for outside clicks is kept for the windows here, they are shown and hidden by Tauri
 #[derive(Default, Debug)]
-pub struct SpotlightManager {
-    pub config: PluginConfig,
-    registered_window: Mutex<Vec<String>>,
+pub(crate) struct NativeWindows {
+    outside_clicks: outside_click::OutsideClicks,
 }
 
-impl SpotlightManager {
-    pub fn new(config: PluginConfig) -> Self {
-        let mut manager = Self::default();
-        manager.config = config;
-        manager
+impl NativeWindows {
+    /// Applies the parts of the config that depend on the platform to a window being registered
+    pub(crate) fn set_up(&self, window: &Window<Wry>, window_config: &WindowConfig) -> Result<(), Error> {
+        if window_config.should_stay_on_top() {
+            window.set_always_on_top(true)?;
+        }
+        if window_config.should_skip_taskbar() {
+            window.set_skip_taskbar(true)?;
+            hide_from_window_switcher(window)?;
+        }
+        if window_config.should_hide_on_outside_click() {
+            self.outside_clicks.watch(window)?;
+        }
+        Ok(())
+    }
+
+    /// Lets go of a window that is no longer registered
+    pub(crate) fn forget(&self, label: &str) -> Result<(), Error> {
+        self.outside_clicks.unwatch(label)
+    }
+
+    pub(crate) fn set_auto_hide(&self, _label: &str, _auto_hide: bool) -> Result<(), Error> {
+        Ok(())
     }
 
-    fn get_window_config(&self, window: &Window<Wry>) -> Option<WindowConfig> {
-        if let Some(window_configs) = self.config.windows.clone() {
-            for window_config in window_configs {
-                if window.label() == window_config.label {
-                    return Some(window_config.clone());
+    /// Shows a hidden window, or one that is fading out. Returns whether it was hidden
+    pub(crate) fn show(&self, window: &Window<Wry>, window_config: &WindowConfig, hiding: bool, grab_focus: bool) -> Result<bool, Error> {
+        let was_visible = window.is_visible().map_err(|_| Error::FailedToCheckWindowVisibility)?;
+        if was_visible && !hiding {
+            return Ok(false);
+        }
+        animation::start_show(window, &window_config.animation)?;
+        if grab_focus {
+            window.show().map_err(|_| Error::FailedToShowWindow)?;
+        } else {
+            show_without_activating(window)?;
+        }
+        if window_config.should_stay_on_top() {
+            keep_on_top(window)?;
+        }
+        if grab_focus {
+            if window_config.should_force_foreground() {
+                // Not being in front is no reason to fail showing the window
+                if let Err(err) = bring_window_to_front(window) {
+                    log_warn!("failed to bring spotlight window {} to front: {}", window.label(), err);
                 }
             }
+            // A window that is shown but didn't get focus, e.g. because the window manager
+            // refused, is still usable
+            if let Err(err) = window.set_focus().map_err(|_| Error::FailedToFocusWindow) {
+                log_warn!("spotlight window {} is shown but not focused: {}", window.label(), err);
+            }
         }
-        None
-    }
-
-    pub fn init_spotlight_window(&self, window: &Window<Wry>) -> Result<(), Error> {
-        let window_config = match self.get_window_config(&window) {
-            Some(window_config) => window_config,
-            None => return Ok(()),
-        };
-        let label = window.label().to_string();
-        let handle = window.app_handle();
-        let state = handle.state::<SpotlightManager>();
-        let mut registered_window = state
-            .registered_window
-            .lock()
-            .map_err(|_| Error::Mutex(String::from("failed to lock registered window")))?;
-        let registered = registered_window.contains(&label);
-        if !registered {
-            register_shortcut_for_window(&window, &window_config)?;
-            register_close_shortcut(&window)?;
-            handle_focus_state_change(&window);
-            registered_window.push(label);
-        }
-        Ok(())
+        Ok(!was_visible)
     }
 
-    pub fn show(&self, window: &Window<Wry>) -> Result<(), Error> {
+    /// Hides a visible window. Returns whether it was visible
+    pub(crate) fn hide(&self, window: &Window<Wry>) -> Result<bool, Error> {
         if !window.is_visible().map_err(|_| Error::FailedToCheckWindowVisibility)? {
-            window.show().map_err(|_| Error::FailedToShowWindow)?;
-            window.set_focus().map_err(|_| Error::FailedToShowWindow)?;
+            return Ok(false);
         }
-        Ok(())
+        window.hide().map_err(|_| Error::FailedToHideWindow)?;
+        hide_natively(window)?;
+        Ok(true)
     }
+}
 
-    pub fn hide(&self, window: &Window<Wry>) -> Result<(), Error> {
-        if window.is_visible().map_err(|_| Error::FailedToCheckWindowVisibility)? {
-            window.hide().map_err(|_| Error::FailedToHideWindow)?;
-        }
-        Ok(())
+/// Puts the window back on top, in case the flag was dropped while it was hidden. On Windows this
+/// happens right away, so that the window is already topmost when it is brought to the foreground
+#[cfg(target_os = "windows")]
+fn keep_on_top(window: &Window<Wry>) -> Result<(), Error> {
+    use winapi::shared::windef::HWND;
+    use winapi::um::winuser::{SetWindowPos, HWND_TOPMOST, SWP_NOACTIVATE, SWP_NOMOVE, SWP_NOSIZE};
+
+    let hwnd = window.hwnd().map_err(|_| Error::FailedToGetWindowHandle)?.0 as HWND;
+    unsafe { SetWindowPos(hwnd, HWND_TOPMOST, 0, 0, 0, 0, SWP_NOMOVE | SWP_NOSIZE | SWP_NOACTIVATE) };
+    Ok(())
+}
+
+/// Shows the window without activating it. Tauri doesn't know it is visible then, so hiding it
+/// has to go through `hide_natively` as well
+#[cfg(target_os = "windows")]
+fn show_without_activating(window: &Window<Wry>) -> Result<(), Error> {
+    use winapi::shared::windef::HWND;
+    use winapi::um::winuser::{ShowWindow, SW_SHOWNOACTIVATE};
+
+    let hwnd = window.hwnd().map_err(|_| Error::FailedToGetWindowHandle)?.0 as HWND;
+    unsafe { ShowWindow(hwnd, SW_SHOWNOACTIVATE) };
+    Ok(())
+}
+
+/// Hides a window that may have been shown behind the back of Tauri by `show_without_activating`
+#[cfg(target_os = "windows")]
+fn hide_natively(window: &Window<Wry>) -> Result<(), Error> {
+    use winapi::shared::windef::HWND;
+    use winapi::um::winuser::{ShowWindow, SW_HIDE};
+
+    let hwnd = window.hwnd().map_err(|_| Error::FailedToGetWindowHandle)?.0 as HWND;
+    unsafe { ShowWindow(hwnd, SW_HIDE) };
+    Ok(())
+}
+
+/// Window managers decide on their own whether a shown window gets focus
+#[cfg(not(target_os = "windows"))]
+fn show_without_activating(window: &Window<Wry>) -> Result<(), Error> {
+    window.show().map_err(|_| Error::FailedToShowWindow)
+}
+
+#[cfg(not(target_os = "windows"))]
+fn hide_natively(_window: &Window<Wry>) -> Result<(), Error> {
+    Ok(())
+}
+
+#[cfg(not(target_os = "windows"))]
+fn keep_on_top(window: &Window<Wry>) -> Result<(), Error> {
+    window.set_always_on_top(true)?;
+    Ok(())
+}
+
+/// Leaves the window out of Alt+Tab, which only skips tool windows
+#[cfg(target_os = "windows")]
+fn hide_from_window_switcher(window: &Window<Wry>) -> Result<(), Error> {
+    use winapi::shared::windef::HWND;
+    use winapi::um::winuser::{
+        GetWindowLongPtrW, SetWindowLongPtrW, SetWindowPos, GWL_EXSTYLE, SWP_FRAMECHANGED, SWP_NOACTIVATE,
+        SWP_NOMOVE, SWP_NOSIZE, SWP_NOZORDER, WS_EX_APPWINDOW, WS_EX_TOOLWINDOW,
+    };
+
+    let hwnd = window.hwnd().map_err(|_| Error::FailedToGetWindowHandle)?.0 as HWND;
+    unsafe {
+        let style = GetWindowLongPtrW(hwnd, GWL_EXSTYLE);
+        SetWindowLongPtrW(hwnd, GWL_EXSTYLE, (style | WS_EX_TOOLWINDOW as isize) & !(WS_EX_APPWINDOW as isize));
+        // Styles are cached until the frame changes
+        SetWindowPos(hwnd, std::ptr::null_mut(), 0, 0, 0, 0, SWP_FRAMECHANGED | SWP_NOMOVE | SWP_NOSIZE | SWP_NOZORDER | SWP_NOACTIVATE);
     }
+    Ok(())
 }
 
-fn register_shortcut_for_window(window: &Window<Wry>, window_config: &WindowConfig) -> Result<(), Error> {
-    let window = window.to_owned();
-    let mut shortcut_manager = window.app_handle().global_shortcut_manager();
-    shortcut_manager.register(&window_config.shortcut, move || {
-        let app_handle = window.app_handle();
-        let manager = app_handle.state::<SpotlightManager>();
-        if window.is_visible().unwrap() {
-            manager.hide(&window).unwrap();
-        } else {
-            manager.show(&window).unwrap();
+#[cfg(not(target_os = "windows"))]
+fn hide_from_window_switcher(_window: &Window<Wry>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Restores the window if it's minimized and brings it in front of other apps
+#[cfg(target_os = "windows")]
+fn bring_window_to_front(window: &Window<Wry>) -> Result<(), Error> {
+    use winapi::shared::windef::HWND;
+    use winapi::um::winuser::{IsIconic, SetForegroundWindow, ShowWindow, SW_RESTORE};
+
+    let hwnd = window.hwnd().map_err(|_| Error::FailedToGetWindowHandle)?.0 as HWND;
+    unsafe {
+        if IsIconic(hwnd) != 0 {
+            ShowWindow(hwnd, SW_RESTORE);
         }
-    }).map_err(|_| Error::Other(String::from("failed to register shortcut")))?;
+        SetForegroundWindow(hwnd);
+    }
     Ok(())
 }
 
-fn register_close_shortcut(window: &Window<Wry>) -> Result<(), Error> {
-    let window = window.to_owned();
-    let mut shortcut_manager = window.app_handle().global_shortcut_manager();
-    let app_handle = window.app_handle();
-    let manager = app_handle.state::<SpotlightManager>();
-    if let Some(close_shortcut) = &manager.config.global_close_shortcut {
-        if let Ok(registered) = shortcut_manager.is_registered(close_shortcut) {
-            if !registered {
-                shortcut_manager.register(close_shortcut, move || {
-                    let app_handle = window.app_handle();
-                    let state = app_handle.state::<SpotlightManager>();
-                    let registered_window = state.registered_window.lock().unwrap();
-                    let window_labels = registered_window.clone();
-                    std::mem::drop(registered_window);
-                    for label in window_labels {
-                        if let Some(window) = app_handle.get_window(&label) {
-                            window.hide().unwrap();
-                        }
-                    }
-                }).map_err(tauri::Error::Runtime)?;
-            }
-        } else {
-            return Err(Error::Other(String::from("failed to register shortcut")));
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+fn bring_window_to_front(_window: &Window<Wry>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Returns the window in front of all others unless it is this one, to give focus back to later
+#[cfg(target_os = "windows")]
+pub(crate) fn capture_previous_focus(window: &Window<Wry>) -> Result<Option<isize>, Error> {
+    use winapi::shared::windef::HWND;
+    use winapi::um::winuser::GetForegroundWindow;
+
+    let hwnd = window.hwnd().map_err(|_| Error::FailedToGetWindowHandle)?.0 as HWND;
+    let foreground = unsafe { GetForegroundWindow() };
+    Ok((!foreground.is_null() && foreground != hwnd).then(|| foreground as isize))
+}
+
+#[cfg(not(target_os = "windows"))]
+pub(crate) fn capture_previous_focus(_window: &Window<Wry>) -> Result<Option<isize>, Error> {
+    Ok(None)
+}
+
+/// Brings the previously focused window back in front, unless it was closed in the meantime or the
+/// user already switched away from the spotlight window
+#[cfg(target_os = "windows")]
+pub(crate) fn restore_previous_focus(window: &Window<Wry>, previous: isize) -> Result<(), Error> {
+    use winapi::shared::windef::HWND;
+    use winapi::um::winuser::{GetForegroundWindow, IsWindow, SetForegroundWindow};
+
+    let hwnd = window.hwnd().map_err(|_| Error::FailedToGetWindowHandle)?.0 as HWND;
+    let previous = previous as HWND;
+    unsafe {
+        if GetForegroundWindow() == hwnd && IsWindow(previous) != 0 {
+            SetForegroundWindow(previous);
         }
     }
     Ok(())
 }
 
-fn unregister_close_shortcut(window: &Window<Wry>) -> Result<(), Error> {
-    let window = window.to_owned();
-    let mut shortcut_manager = window.app_handle().global_shortcut_manager();
-    let app_handle = window.app_handle();
-    let manager = app_handle.state::<SpotlightManager>();
-    if let Some(close_shortcut) = manager.config.global_close_shortcut.clone() {
-        if let Ok(registered) = shortcut_manager.is_registered(&close_shortcut) {
-            if registered {
-                shortcut_manager.unregister(&close_shortcut).map_err(tauri::Error::Runtime)?;
+#[cfg(not(target_os = "windows"))]
+pub(crate) fn restore_previous_focus(_window: &Window<Wry>, _previous: isize) -> Result<(), Error> {
+    Ok(())
+}
+
+
+/// Positions a given window in the work area of the monitor it is on, as laid out by the preset
+pub(crate) fn position_window_on_its_monitor(window: &Window<Wry>, preset: PositionPreset) -> Result<(), Error> {
+    if let Some(work_area) = get_work_area_of_window(window)? {
+        position_window_in_work_area(window, &work_area, preset)?;
+    }
+    Ok(())
+}
+
+/// Returns the work area `show` lays the window out in by its position preset, `None` if the window
+/// has no preset and stays where it is. A window centered on its own monitor goes to the primary
+/// monitor until it was shown, as its position means nothing before
+fn preset_work_area(window: &Window<Wry>, window_config: &WindowConfig, shown_before: bool) -> Result<Option<WorkArea>, Error> {
+    match window_config.position {
+        Some(WindowPosition::CenterOwnMonitor) if shown_before => get_work_area_of_window(window),
+        Some(WindowPosition::CenterOwnMonitor) => match window.primary_monitor().map_err(Error::MonitorQueryFailed)? {
+            Some(monitor) => Ok(Some(work_area_of_monitor(&monitor))),
+            None => get_work_area_of_window(window),
+        },
+        // A preset alone is enough to place the window on the active monitor
+        Some(WindowPosition::CenterActiveMonitor) => get_work_area_with_cursor(window),
+        Some(WindowPosition::AtCursor { .. }) => match cursor_position()? {
+            Some(cursor) => get_work_area_at(window, cursor),
+            None => get_work_area_with_cursor(window),
+        },
+        None if window_config.position_preset.is_some() => get_work_area_with_cursor(window),
+        None => Ok(None),
+    }
+}
+
+/// Places the window at the cursor or as laid out by its position preset, leaving a window with
+/// neither where it is
+pub(crate) fn position_window_by_preset(window: &Window<Wry>, window_config: &WindowConfig, shown_before: bool) -> Result<(), Error> {
+    if let Some(position) = at_cursor_position(window, window_config)? {
+        window.set_position(position)?;
+    } else if let Some(work_area) = preset_work_area(window, window_config, shown_before)? {
+        position_window_in_work_area(window, &work_area, window_config.placement())?;
+    }
+    Ok(())
+}
+
+/// Whether `show` lays the window out by its position preset, a window with neither a position
+/// nor a preset stays where it is
+pub(crate) fn is_laid_out(window_config: &WindowConfig) -> bool {
+    window_config.position.is_some() || window_config.position_preset.is_some()
+}
+
+/// Describes the monitor containing the point, along with its work area
+pub(crate) fn target_monitor_at(window: &Window<Wry>, point: PhysicalPosition<i32>) -> Result<Option<TargetMonitor>, Error> {
+    target_monitor_of(window, get_work_area_at(window, point)?)
+}
+
+/// Describes the monitor `show` lays the window out on by its position preset
+pub(crate) fn preset_target_monitor(window: &Window<Wry>, window_config: &WindowConfig, shown_before: bool) -> Result<Option<TargetMonitor>, Error> {
+    target_monitor_of(window, preset_work_area(window, window_config, shown_before)?)
+}
+
+/// Describes the monitor the window is on
+pub(crate) fn target_monitor_of_window(window: &Window<Wry>) -> Result<Option<TargetMonitor>, Error> {
+    target_monitor_of(window, get_work_area_of_window(window)?)
+}
+
+/// Returns where a window shown at the cursor goes, `None` for other windows or if the cursor
+/// can't be found, those are laid out by their position preset
+fn at_cursor_position(window: &Window<Wry>, window_config: &WindowConfig) -> Result<Option<PhysicalPosition<i32>>, Error> {
+    let (offset_x, offset_y) = match window_config.position {
+        Some(WindowPosition::AtCursor { offset_x, offset_y }) => (offset_x, offset_y),
+        _ => return Ok(None),
+    };
+    let cursor = match cursor_position()? {
+        Some(cursor) => cursor,
+        None => return Ok(None),
+    };
+    let work_area = match get_work_area_at(window, cursor)? {
+        Some(work_area) => work_area,
+        None => return Ok(None),
+    };
+    // The window is rescaled by the system once it lands on a monitor with another DPI
+    let window_size = window.outer_size().map_err(Error::position_failed("failed to get window size"))?;
+    let (width, height) = rescale(
+        window_size.width as f64,
+        window_size.height as f64,
+        window.scale_factor().map_err(Error::position_failed("failed to get scale factor"))?,
+        work_area.scale_factor,
+    );
+    let x = cursor.x as f64 + offset_x as f64 * work_area.scale_factor;
+    let y = cursor.y as f64 + offset_y as f64 * work_area.scale_factor;
+    Ok(Some(PhysicalPosition {
+        x: clamp_in_range(work_area.position.x as f64, work_area.size.width as f64, width, x) as i32,
+        y: clamp_in_range(work_area.position.y as f64, work_area.size.height as f64, height, y) as i32,
+    }))
+}
+
+/// Positions a given window on the monitor, as laid out by the preset
+pub(crate) fn position_window_on_monitor(window: &Window<Wry>, monitor: &Monitor, preset: PositionPreset) -> Result<(), Error> {
+    position_window_in_work_area(window, &work_area_of_monitor(monitor), preset)
+}
+
+/// The whole monitor as a work area
+fn work_area_of_monitor(monitor: &Monitor) -> WorkArea {
+    WorkArea {
+        position: *monitor.position(),
+        size: *monitor.size(),
+        scale_factor: monitor.scale_factor(),
+    }
+}
+
+/// Describes the monitor the work area belongs to
+fn target_monitor_of(window: &Window<Wry>, work_area: Option<WorkArea>) -> Result<Option<TargetMonitor>, Error> {
+    match work_area {
+        Some(work_area) => Ok(monitor_at(window, work_area.position)?
+            .map(|monitor| TargetMonitor::new(&monitor, work_area.position, work_area.size))),
+        None => Ok(None),
+    }
+}
+
+fn position_window_in_work_area(window: &Window<Wry>, work_area: &WorkArea, preset: PositionPreset) -> Result<(), Error> {
+    // The window is rescaled by the system once it lands on a monitor with another DPI
+    let window_size = window.outer_size().map_err(Error::position_failed("failed to get window size"))?;
+    let (width, height) = rescale(
+        window_size.width as f64,
+        window_size.height as f64,
+        window.scale_factor().map_err(Error::position_failed("failed to get scale factor"))?,
+        work_area.scale_factor,
+    );
+    place_in_work_area(window, work_area, preset, width, height)?;
+    // The system may size the window differently than expected, e.g. when it rounds the new size
+    // or the window landed on another monitor, so it is placed again with the size it ended up with
+    let placed_size = window.outer_size().map_err(Error::position_failed("failed to get window size"))?;
+    if (placed_size.width as f64 - width).abs() > 1.0 || (placed_size.height as f64 - height).abs() > 1.0 {
+        place_in_work_area(window, work_area, preset, placed_size.width as f64, placed_size.height as f64)?;
+    }
+    Ok(())
+}
+
+fn place_in_work_area(window: &Window<Wry>, work_area: &WorkArea, preset: PositionPreset, width: f64, height: f64) -> Result<(), Error> {
+    window.set_position(PhysicalPosition {
+        x: center_in_range(work_area.position.x as f64, work_area.size.width as f64, width) as i32,
+        y: (work_area.position.y as f64 + offset_from_top(preset, work_area.size.height as f64, height)) as i32,
+    })?;
+    Ok(())
+}
+
+struct WorkArea {
+    pub position: PhysicalPosition<i32>,
+    pub size: PhysicalSize<u32>,
+    pub scale_factor: f64,
+}
+
+/// Returns the work area of the monitor with cursor
+#[cfg(target_os = "windows")]
+fn get_work_area_with_cursor(window: &Window<Wry>) -> Result<Option<WorkArea>, Error> {
+    use winapi::shared::windef::POINT;
+    use winapi::um::winuser::{GetCursorPos, MonitorFromPoint, MONITOR_DEFAULTTONEAREST};
+
+    let mut cursor = POINT { x: 0, y: 0 };
+    if unsafe { GetCursorPos(&mut cursor) } == 0 {
+        return Ok(None);
+    }
+    get_work_area_of_monitor(window, unsafe { MonitorFromPoint(cursor, MONITOR_DEFAULTTONEAREST) })
+}
+
+/// Returns where the cursor is in physical pixels
+#[cfg(target_os = "windows")]
+fn cursor_position() -> Result<Option<PhysicalPosition<i32>>, Error> {
+    use winapi::shared::windef::POINT;
+    use winapi::um::winuser::GetCursorPos;
+
+    let mut cursor = POINT { x: 0, y: 0 };
+    if unsafe { GetCursorPos(&mut cursor) } == 0 {
+        return Ok(None);
+    }
+    Ok(Some(PhysicalPosition { x: cursor.x, y: cursor.y }))
+}
+
+/// Returns the work area of the monitor containing the point, or the nearest one
+#[cfg(target_os = "windows")]
+fn get_work_area_at(window: &Window<Wry>, point: PhysicalPosition<i32>) -> Result<Option<WorkArea>, Error> {
+    use winapi::shared::windef::POINT;
+    use winapi::um::winuser::{MonitorFromPoint, MONITOR_DEFAULTTONEAREST};
+
+    let point = POINT { x: point.x, y: point.y };
+    get_work_area_of_monitor(window, unsafe { MonitorFromPoint(point, MONITOR_DEFAULTTONEAREST) })
+}
+
+/// Returns the work area of the monitor the window is on
+#[cfg(target_os = "windows")]
+fn get_work_area_of_window(window: &Window<Wry>) -> Result<Option<WorkArea>, Error> {
+    use winapi::shared::windef::HWND;
+    use winapi::um::winuser::{MonitorFromWindow, MONITOR_DEFAULTTONEAREST};
+
+    let hwnd = window.hwnd().map_err(|_| Error::FailedToGetWindowHandle)?.0 as HWND;
+    get_work_area_of_monitor(window, unsafe { MonitorFromWindow(hwnd, MONITOR_DEFAULTTONEAREST) })
+}
+
+#[cfg(target_os = "windows")]
+fn get_work_area_of_monitor(window: &Window<Wry>, hmonitor: winapi::shared::windef::HMONITOR) -> Result<Option<WorkArea>, Error> {
+    use winapi::um::winuser::{GetMonitorInfoW, MONITORINFO};
+
+    let mut info: MONITORINFO = unsafe { std::mem::zeroed() };
+    info.cbSize = std::mem::size_of::<MONITORINFO>() as u32;
+    if unsafe { GetMonitorInfoW(hmonitor, &mut info) } == 0 {
+        return Ok(None);
+    }
+    let (monitor, work) = (info.rcMonitor, info.rcWork);
+    // Tauri knows the scale factor of each monitor, match it by the monitor origin
+    let scale_factor = window
+        .available_monitors()
+        .map_err(Error::MonitorQueryFailed)?
+        .iter()
+        .find(|m| m.position().x == monitor.left && m.position().y == monitor.top)
+        .map(|m| m.scale_factor())
+        .unwrap_or(window.scale_factor().map_err(Error::position_failed("failed to get scale factor"))?);
+    Ok(Some(WorkArea {
+        position: PhysicalPosition { x: work.left, y: work.top },
+        size: PhysicalSize {
+            width: (work.right - work.left) as u32,
+            height: (work.bottom - work.top) as u32,
+        },
+        scale_factor,
+    }))
+}
+
+/// Returns the area of the monitor the cursor is on, or of the one the window is currently on where
+/// the cursor can't be found
+#[cfg(not(target_os = "windows"))]
+fn get_work_area_with_cursor(window: &Window<Wry>) -> Result<Option<WorkArea>, Error> {
+    if let Some(cursor) = cursor_position()? {
+        if let Some(work_area) = get_work_area_at(window, cursor)? {
+            return Ok(Some(work_area));
+        }
+    }
+    get_work_area_of_window(window)
+}
+
+#[cfg(target_os = "linux")]
+fn cursor_position() -> Result<Option<PhysicalPosition<i32>>, Error> {
+    Ok(linux::cursor_position())
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+fn cursor_position() -> Result<Option<PhysicalPosition<i32>>, Error> {
+    Ok(None)
+}
+
+/// Returns the area of the monitor containing the point
+#[cfg(not(target_os = "windows"))]
+fn get_work_area_at(window: &Window<Wry>, point: PhysicalPosition<i32>) -> Result<Option<WorkArea>, Error> {
+    Ok(monitor_at(window, point)?.map(|monitor| work_area_of_monitor(&monitor)))
+}
+
+/// Returns the area of the monitor the window is on
+#[cfg(not(target_os = "windows"))]
+fn get_work_area_of_window(window: &Window<Wry>) -> Result<Option<WorkArea>, Error> {
+    Ok(window.current_monitor().map_err(Error::MonitorQueryFailed)?.map(|monitor| WorkArea {
+        position: *monitor.position(),
+        size: *monitor.size(),
+        scale_factor: monitor.scale_factor(),
+    }))
+}
+
+#[cfg(target_os = "windows")]
+mod outside_click {
+    use std::sync::{
+        atomic::{AtomicIsize, Ordering},
+        Mutex,
+    };
+    use lazy_static::lazy_static;
+    use tauri::{AppHandle, Manager, Window, Wry};
+    use winapi::shared::minwindef::{LPARAM, LRESULT, WPARAM};
+    use winapi::shared::windef::{HWND, POINT, RECT};
+    use winapi::um::winuser::{
+        CallNextHookEx, GetWindowRect, IsWindowVisible, PtInRect, SetWindowsHookExW, MSLLHOOKSTRUCT,
+        WH_MOUSE_LL, WM_LBUTTONDOWN, WM_MBUTTONDOWN, WM_RBUTTONDOWN,
+    };
+    use crate::spotlight::SpotlightManager;
+    use crate::Error;
+
+    lazy_static! {
+        /// The app whose windows the hook looks at, as a hook is called without any context
+        static ref HOOKED_APP: Mutex<Option<AppHandle<Wry>>> = Mutex::new(None);
+    }
+
+    /// The windows hiding on outside clicks, watched by a single low level mouse hook
+    #[derive(Default, Debug)]
+    pub(super) struct OutsideClicks {
+        /// Watched windows along with their HWND, which can be queried without the event loop
+        windows: Mutex<Vec<(isize, Window<Wry>)>>,
+        hook: AtomicIsize,
+    }
+
+    impl OutsideClicks {
+        /// Hides the given window when a mouse button is pressed outside of it
+        pub(super) fn watch(&self, window: &Window<Wry>) -> Result<(), Error> {
+            let hwnd = window.hwnd().map_err(|_| Error::FailedToGetWindowHandle)?.0;
+            let mut windows = self
+                .windows
+                .lock()
+                .map_err(|_| Error::Mutex(String::from("failed to lock watched windows")))?;
+            windows.retain(|(_, watched)| watched.label() != window.label());
+            windows.push((hwnd, window.clone()));
+            std::mem::drop(windows);
+            let mut hooked_app = HOOKED_APP
+                .lock()
+                .map_err(|_| Error::Mutex(String::from("failed to lock hooked app")))?;
+            if hooked_app.is_none() {
+                *hooked_app = Some(window.app_handle());
+            }
+            std::mem::drop(hooked_app);
+            // Low level hooks are called on the thread that installed them, which needs a message loop
+            let app_handle = window.app_handle();
+            window.run_on_main_thread(move || {
+                let manager = app_handle.state::<SpotlightManager>();
+                let hook = &manager.native_windows().outside_clicks.hook;
+                if hook.load(Ordering::SeqCst) == 0 {
+                    let installed = unsafe { SetWindowsHookExW(WH_MOUSE_LL, Some(mouse_proc), std::ptr::null_mut(), 0) };
+                    hook.store(installed as isize, Ordering::SeqCst);
+                }
+            })?;
+            Ok(())
+        }
+
+        /// Stops hiding the window with the given label on outside clicks
+        pub(super) fn unwatch(&self, label: &str) -> Result<(), Error> {
+            let mut windows = self
+                .windows
+                .lock()
+                .map_err(|_| Error::Mutex(String::from("failed to lock watched windows")))?;
+            windows.retain(|(_, watched)| watched.label() != label);
+            Ok(())
+        }
+    }
+
+    unsafe extern "system" fn mouse_proc(code: i32, w_param: WPARAM, l_param: LPARAM) -> LRESULT {
+        let is_button_down = matches!(w_param as u32, WM_LBUTTONDOWN | WM_RBUTTONDOWN | WM_MBUTTONDOWN);
+        if code >= 0 && is_button_down {
+            let point = (*(l_param as *const MSLLHOOKSTRUCT)).pt;
+            // Never block inside the hook, a missed click is better than a stalled mouse
+            let hooked_app = match HOOKED_APP.try_lock() {
+                Ok(hooked_app) => hooked_app.clone(),
+                Err(_) => None,
+            };
+            if let Some(app_handle) = hooked_app {
+                let manager = app_handle.state::<SpotlightManager>();
+                if let Ok(windows) = manager.native_windows().outside_clicks.windows.try_lock() {
+                    hide_clicked_outside(&windows, point);
+                };
+            }
+        }
+        CallNextHookEx(std::ptr::null_mut(), code, w_param, l_param)
+    }
+
+    unsafe fn hide_clicked_outside(windows: &[(isize, Window<Wry>)], point: POINT) {
+        for (hwnd, window) in windows.iter() {
+            let hwnd = *hwnd as HWND;
+            let mut rect: RECT = std::mem::zeroed();
+            let visible = IsWindowVisible(hwnd) != 0;
+            if visible && GetWindowRect(hwnd, &mut rect) != 0 && PtInRect(&rect, point) == 0 {
+                let w = window.clone();
+                let _ = window.run_on_main_thread(move || {
+                    let app_handle = w.app_handle();
+                    let manager = app_handle.state::<SpotlightManager>();
+                    if manager.is_auto_hide_suspended(w.label()).unwrap_or(false)
+                        || manager.is_within_min_visible(&w).unwrap_or(false)
+                    {
+                        return;
+                    }
+                    if let Err(err) = manager.hide(&w) {
+                        log_error!("failed to hide spotlight window {}: {}", w.label(), err);
+                    }
+                });
             }
-        } else {
-            return Err(Error::Other(String::from("failed to unregister shortcut")));
         }
     }
-    Ok(())
 }
 
-fn handle_focus_state_change(window: &Window<Wry>) {
-    let w = window.to_owned();
-    window.on_window_event(move |event| {
-        if let WindowEvent::Focused(false) = event {
-            unregister_close_shortcut(&w).unwrap(); // FIXME:
-            w.hide().unwrap();
-        } else {
-            register_close_shortcut(&w).unwrap(); // FIXME:
+/// Outside clicks can only be detected on Windows, losing focus stands in for them elsewhere
+#[cfg(not(target_os = "windows"))]
+mod outside_click {
+    use tauri::{Window, Wry};
+    use crate::Error;
+
+    #[derive(Default, Debug)]
+    pub(super) struct OutsideClicks;
+
+    impl OutsideClicks {
+        pub(super) fn watch(&self, _window: &Window<Wry>) -> Result<(), Error> {
+            Ok(())
         }
-    });
+
+        pub(super) fn unwatch(&self, _label: &str) -> Result<(), Error> {
+            Ok(())
+        }
+    }
 }
@@ -0,0 +1,78 @@
+use tauri::{Manager, Monitor, PhysicalPosition, PhysicalSize, Window, Wry};
+use crate::{Error, WindowConfig};
+use crate::shortcut_backend::{shortcut_backend, ShortcutBackend};
+
+#[derive(serde::Serialize, Debug, Clone, PartialEq)]
+pub struct SpotlightState {
+    pub visible: bool,
+    /// Whether every toggle shortcut of the window is currently registered
+    pub shortcut_registered: bool,
+    pub auto_hide: bool,
+}
+
+/// The monitor a window is shown on, in physical pixels
+#[derive(serde::Serialize, Debug, Clone, PartialEq)]
+pub struct TargetMonitor {
+    pub name: Option<String>,
+    pub position: PhysicalPosition<i32>,
+    pub size: PhysicalSize<u32>,
+    pub scale_factor: f64,
+    /// The part of the monitor the window is laid out in, without the taskbar, dock or menu bar
+    pub work_area_position: PhysicalPosition<i32>,
+    pub work_area_size: PhysicalSize<u32>,
+}
+
+impl TargetMonitor {
+    pub(crate) fn new(monitor: &Monitor, work_area_position: PhysicalPosition<i32>, work_area_size: PhysicalSize<u32>) -> Self {
+        Self {
+            name: monitor.name().cloned(),
+            position: *monitor.position(),
+            size: *monitor.size(),
+            scale_factor: monitor.scale_factor(),
+            work_area_position,
+            work_area_size,
+        }
+    }
+}
+
+/// Returns the monitor containing the point
+pub(crate) fn monitor_at(window: &Window<Wry>, point: PhysicalPosition<i32>) -> Result<Option<Monitor>, Error> {
+    Ok(window.available_monitors().map_err(Error::MonitorQueryFailed)?.into_iter().find(|monitor| {
+        let (position, size) = (monitor.position(), monitor.size());
+        point.x >= position.x
+            && point.x < position.x + size.width as i32
+            && point.y >= position.y
+            && point.y < position.y + size.height as i32
+    }))
+}
+
+/// Whether the center of the window is on one of the monitors. Without any monitors to go by, the
+/// window is taken to be where it should be
+pub(crate) fn is_on_screen(window: &Window<Wry>) -> Result<bool, Error> {
+    if window.available_monitors().map_err(Error::MonitorQueryFailed)?.is_empty() {
+        return Ok(true);
+    }
+    let (position, size) = (window.outer_position()?, window.outer_size()?);
+    let center = PhysicalPosition {
+        x: position.x + size.width as i32 / 2,
+        y: position.y + size.height as i32 / 2,
+    };
+    Ok(monitor_at(window, center)?.is_some())
+}
+
+pub(crate) fn get_spotlight_state(window: &Window<Wry>, window_config: &WindowConfig) -> Result<SpotlightState, Error> {
+    let visible = window.is_visible().map_err(|_| Error::FailedToCheckWindowVisibility)?;
+    let shortcut_manager = shortcut_backend(&window.app_handle());
+    let shortcuts = window_config.all_shortcuts();
+    let mut shortcut_registered = !shortcuts.is_empty();
+    for shortcut in &shortcuts {
+        if !shortcut_manager.is_registered(shortcut).map_err(Error::query_failed(shortcut))? {
+            shortcut_registered = false;
+        }
+    }
+    Ok(SpotlightState {
+        visible,
+        shortcut_registered,
+        auto_hide: window_config.should_auto_hide(),
+    })
+}
@@ -0,0 +1,145 @@
+use std::{
+    collections::HashMap,
+    sync::{Condvar, Mutex, MutexGuard},
+    thread::{self, ThreadId},
+    time::Duration,
+};
+use crate::Error;
+
+/// Waiting any longer means the other thread is most likely blocked on the main thread, which would
+/// be waiting for us in turn, so locking fails rather than freezing the app
+const LOCK_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Thread holding the lock of each window and how many times it locked it
+type Owners = HashMap<String, (ThreadId, usize)>;
+
+/// Re-entrant per-window locks, so that checking the visibility of a window and acting on it is
+/// atomic with respect to other shows and hides of the same window
+#[derive(Default, Debug)]
+pub(crate) struct WindowLocks {
+    owners: Mutex<Owners>,
+    released: Condvar,
+}
+
+pub(crate) struct WindowLockGuard<'a> {
+    locks: &'a WindowLocks,
+    label: String,
+}
+
+impl WindowLocks {
+    fn lock_owners(&self) -> Result<MutexGuard<'_, Owners>, Error> {
+        self.owners.lock().map_err(|_| Error::Mutex(String::from("failed to lock window locks")))
+    }
+
+    pub(crate) fn lock(&self, label: &str) -> Result<WindowLockGuard<'_>, Error> {
+        let current = thread::current().id();
+        let mut owners = self.lock_owners()?;
+        loop {
+            match owners.get_mut(label) {
+                Some((owner, depth)) if *owner == current => {
+                    *depth += 1;
+                    break;
+                }
+                Some(_) => {
+                    let (guard, result) = self
+                        .released
+                        .wait_timeout(owners, LOCK_TIMEOUT)
+                        .map_err(|_| Error::Mutex(String::from("failed to lock window locks")))?;
+                    owners = guard;
+                    if result.timed_out() && owners.contains_key(label) {
+                        return Err(Error::Mutex(format!("timed out waiting for the lock of window {}", label)));
+                    }
+                }
+                None => {
+                    owners.insert(label.to_string(), (current, 1));
+                    break;
+                }
+            }
+        }
+        Ok(WindowLockGuard { locks: self, label: label.to_string() })
+    }
+}
+
+impl Drop for WindowLockGuard<'_> {
+    fn drop(&mut self) {
+        let mut owners = match self.locks.lock_owners() {
+            Ok(owners) => owners,
+            Err(_) => return,
+        };
+        if let Some((_, depth)) = owners.get_mut(&self.label) {
+            *depth -= 1;
+            if *depth == 0 {
+                owners.remove(&self.label);
+                self.locks.released.notify_all();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{atomic::{AtomicBool, AtomicUsize, Ordering}, Arc, Barrier};
+    use std::{thread, time::Duration};
+    use super::WindowLocks;
+
+    /// Mimics `hide`: checks the visibility, then acts on it after a delay that lets the other
+    /// thread run in between if the two weren't serialized
+    fn hide(locks: &WindowLocks, visible: &AtomicBool, hidden_events: &AtomicUsize) {
+        let _guard = locks.lock("main").unwrap();
+        if visible.load(Ordering::SeqCst) {
+            thread::sleep(Duration::from_millis(20));
+            visible.store(false, Ordering::SeqCst);
+            hidden_events.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn programmatic_hide_and_auto_hide_hide_once() {
+        let locks = Arc::new(WindowLocks::default());
+        let visible = Arc::new(AtomicBool::new(true));
+        let hidden_events = Arc::new(AtomicUsize::new(0));
+        let barrier = Arc::new(Barrier::new(2));
+        let handles: Vec<_> = (0..2).map(|_| {
+            let (locks, visible, hidden_events, barrier) = (locks.clone(), visible.clone(), hidden_events.clone(), barrier.clone());
+            thread::spawn(move || {
+                barrier.wait();
+                hide(&locks, &visible, &hidden_events);
+            })
+        }).collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        assert_eq!(hidden_events.load(Ordering::SeqCst), 1);
+        assert!(!visible.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn lock_is_reentrant_and_per_window() {
+        let locks = WindowLocks::default();
+        let outer = locks.lock("main").unwrap();
+        let inner = locks.lock("main").unwrap();
+        let _other = locks.lock("secondary").unwrap();
+        drop(inner);
+        assert!(locks.owners.lock().unwrap().contains_key("main"));
+        drop(outer);
+        assert!(!locks.owners.lock().unwrap().contains_key("main"));
+    }
+
+    #[test]
+    fn lock_held_too_long_fails() {
+        let locks = Arc::new(WindowLocks::default());
+        let barrier = Arc::new(Barrier::new(2));
+        let holder = {
+            let (locks, barrier) = (locks.clone(), barrier.clone());
+            thread::spawn(move || {
+                let _guard = locks.lock("main").unwrap();
+                barrier.wait();
+                thread::sleep(super::LOCK_TIMEOUT * 2);
+            })
+        };
+        barrier.wait();
+        assert!(locks.lock("main").is_err());
+        holder.join().unwrap();
+        assert!(locks.lock("main").is_ok());
+    }
+}